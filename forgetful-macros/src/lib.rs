@@ -0,0 +1,127 @@
+//! Attribute macros for [`forgetful`](https://docs.rs/forgetful). Enable
+//! its `macros` feature rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Expr, ItemFn, Type};
+
+/**
+Guards a function against re-entrant calls on the same thread.
+
+Each annotated function gets its own thread-local
+[`OwnedObserver`](https://docs.rs/forgetful/latest/forgetful/struct.OwnedObserver.html),
+which notices a key on entry and forgets it on return or panic. A call made
+while another call with the same key is still running on the same thread
+panics, or returns early if `on_reentry` is given.
+
+Arguments, all optional:
+- `key = <expr>`: what identifies a call, evaluated on entry with the
+  arguments in scope. Defaults to `()`, so that any re-entrant call is
+  rejected. The key is stored by value, so borrow or clone arguments rather
+  than moving them.
+- `key_type = <type>`: the type of `key`, which is needed to declare the
+  observer. Required if `key` is given.
+- `on_reentry = <expr>`: the value to return when a call is rejected.
+
+```
+use forgetful::non_reentrant;
+
+#[non_reentrant(key = name.to_string(), key_type = String, on_reentry = Err(name.to_string()))]
+fn load(name: &str, includes: &[&str]) -> Result<(), String> {
+    for include in includes {
+        load(include, includes)?;
+    }
+    Ok(())
+}
+
+assert_eq!(load("a", &[]), Ok(()));
+assert_eq!(load("a", &["b", "b"]), Err("b".to_string()));
+```
+
+Without `on_reentry`, a re-entrant call panics:
+```should_panic
+#[forgetful::non_reentrant]
+fn recurse(depth: u32) {
+    if depth < 2 {
+        recurse(depth + 1);
+    }
+}
+
+recurse(0);
+```
+*/
+#[proc_macro_attribute]
+pub fn non_reentrant(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut key: Option<Expr> = None;
+    let mut key_type: Option<Type> = None;
+    let mut on_reentry: Option<Expr> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("key") {
+            key = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("key_type") {
+            key_type = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("on_reentry") {
+            on_reentry = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `key`, `key_type` or `on_reentry`"));
+        }
+        Ok(())
+    });
+    parse_macro_input!(attr with parser);
+    let function = parse_macro_input!(item as ItemFn);
+
+    if let Some(asyncness) = &function.sig.asyncness {
+        return syn::Error::new(
+            asyncness.span(),
+            "non_reentrant doesn't support async functions",
+        )
+        .into_compile_error()
+        .into();
+    }
+    let (key, key_type) = match (key, key_type) {
+        (Some(key), Some(key_type)) => (quote!(#key), quote!(#key_type)),
+        (None, None) => (quote!(()), quote!(())),
+        (Some(key), None) => {
+            return syn::Error::new(key.span(), "`key` needs a `key_type`")
+                .into_compile_error()
+                .into();
+        }
+        (None, Some(key_type)) => {
+            return syn::Error::new(key_type.span(), "`key_type` needs a `key`")
+                .into_compile_error()
+                .into();
+        }
+    };
+    let name = &function.sig.ident;
+    let rejected = match on_reentry {
+        Some(on_reentry) => quote_spanned!(on_reentry.span()=> return #on_reentry),
+        None => {
+            let message = format!("re-entrant call to `{}`", name);
+            quote!(::core::panic!(#message))
+        }
+    };
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = function;
+    quote! {
+        #(#attrs)*
+        #vis #sig {
+            ::std::thread_local! {
+                static __FORGETFUL_OBSERVER: ::forgetful::OwnedObserver<#key_type> =
+                    ::forgetful::OwnedObserver::new();
+            }
+            let __forgetful_observation =
+                match __FORGETFUL_OBSERVER.with(|observer| observer.notice(#key)) {
+                    ::core::option::Option::Some(observation) => observation,
+                    ::core::option::Option::None => #rejected,
+                };
+            #block
+        }
+    }
+    .into()
+}
@@ -0,0 +1,169 @@
+use core::cell::RefCell;
+use core::cmp::Eq;
+use core::fmt::{self, Debug, Display};
+
+/// The error returned by [`ArrayObserver::notice`] when every slot is
+/// already in use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError {
+    capacity: usize,
+}
+
+impl CapacityError {
+    /// The capacity of the observer that ran out of slots.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "observer is full ({} observations)", self.capacity)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
+
+/// An observation produced by [`ArrayObserver::notice`].
+///
+/// It borrows the observer it came from, so no reference counting is
+/// involved.
+pub struct ArrayObservation<'o, 'a, T, const N: usize>
+where
+    T: 'a + Eq + ?Sized,
+{
+    observer: &'o ArrayObserver<'a, T, N>,
+    slot: usize,
+}
+
+impl<'o, 'a, T, const N: usize> Debug for ArrayObservation<'o, 'a, T, N>
+where
+    T: 'a + Eq + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.observer.slots.borrow()[self.slot] {
+            Some(item) => write!(f, "{:?}", item),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'o, 'a, T, const N: usize> Drop for ArrayObservation<'o, 'a, T, N>
+where
+    T: 'a + Eq + ?Sized,
+{
+    fn drop(&mut self) {
+        self.observer.slots.borrow_mut()[self.slot] = None;
+    }
+}
+
+/**
+ArrayObserver is a fixed-capacity observer that never allocates.
+
+Observations are kept in an inline array of `N` slots and looked up with a
+linear scan, so items only need to be `Eq`. This is a good fit for small `N`
+on embedded or latency-sensitive paths. Noticing an item while all slots are
+taken fails with a [`CapacityError`].
+```
+use forgetful::ArrayObserver;
+let observer = ArrayObserver::<_, 2>::new();
+let foo = observer.notice("foo").unwrap().expect("never seen before");
+assert!(observer.notice("foo").unwrap().is_none());
+let bar = observer.notice("bar").unwrap().expect("never seen before");
+assert!(observer.notice("baz").is_err());
+drop(foo);
+assert!(observer.notice("baz").unwrap().is_some());
+```
+*/
+pub struct ArrayObserver<'a, T, const N: usize>
+where
+    T: 'a + Eq + ?Sized,
+{
+    slots: RefCell<[Option<&'a T>; N]>,
+}
+
+impl<'a, T, const N: usize> Default for ArrayObserver<'a, T, N>
+where
+    T: 'a + Eq + ?Sized,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const N: usize> Debug for ArrayObserver<'a, T, N>
+where
+    T: 'a + Eq + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.slots.borrow().iter().flatten())
+            .finish()
+    }
+}
+
+impl<'a, T, const N: usize> ArrayObserver<'a, T, N>
+where
+    T: 'a + Eq + ?Sized,
+{
+    pub fn new() -> Self {
+        Self {
+            slots: RefCell::new([None; N]),
+        }
+    }
+
+    /// Notices `item`, returning `Ok(None)` if it is already observed, or a
+    /// [`CapacityError`] if it isn't but there is no room to observe it.
+    pub fn notice(
+        &self,
+        item: &'a T,
+    ) -> Result<Option<ArrayObservation<'_, 'a, T, N>>, CapacityError> {
+        let mut slots = self.slots.borrow_mut();
+        if slots.iter().flatten().any(|seen| *seen == item) {
+            return Ok(None);
+        }
+        match slots.iter().position(Option::is_none) {
+            Some(slot) => {
+                slots[slot] = Some(item);
+                Ok(Some(ArrayObservation {
+                    observer: self,
+                    slot,
+                }))
+            }
+            None => Err(CapacityError { capacity: N }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = ArrayObserver::<_, 4>::new();
+        let g = o.notice(&1).unwrap();
+        assert!(g.is_some());
+        assert!(o.notice(&1).unwrap().is_none());
+        drop(g);
+        assert!(o.notice(&1).unwrap().is_some());
+    }
+
+    #[test]
+    fn reports_full_but_not_for_duplicates() {
+        let o = ArrayObserver::<_, 1>::new();
+        let _g = o.notice(&1).unwrap();
+        assert!(o.notice(&1).unwrap().is_none());
+        assert_eq!(o.notice(&2).unwrap_err().capacity(), 1);
+    }
+
+    #[test]
+    fn freed_slots_are_reused() {
+        let o = ArrayObserver::<_, 2>::new();
+        let g1 = o.notice(&1).unwrap();
+        let _g2 = o.notice(&2).unwrap();
+        drop(g1);
+        assert!(o.notice(&3).unwrap().is_some());
+    }
+}
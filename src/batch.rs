@@ -0,0 +1,279 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, Observation, Observer, Recorder};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+
+/**
+A group of observations, which are all forgotten together when it is
+dropped or [released](Self::release_all).
+
+It is returned by [`Observer::notice_all`] and [`Observer::notice_any`], and
+can also collect observations made one at a time.
+```
+use forgetful::{ObservationSet, Observer};
+let observer = Observer::new();
+let mut held = ObservationSet::new();
+for item in ["a", "b", "c"] {
+    held.insert(observer.notice(item).unwrap());
+}
+assert_eq!(observer.len(), 3);
+held.release_all();
+assert!(observer.is_empty() && held.is_empty());
+```
+*/
+pub struct ObservationSet<'a, T, R = HashMap<&'a T, Entry, DefaultHashBuilder>>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    observations: Vec<Observation<'a, T, R>>,
+}
+
+impl<'a, T, R> Default for ObservationSet<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, R> Extend<Observation<'a, T, R>> for ObservationSet<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn extend<I>(&mut self, observations: I)
+    where
+        I: IntoIterator<Item = Observation<'a, T, R>>,
+    {
+        self.observations.extend(observations);
+    }
+}
+
+impl<'a, T, R> FromIterator<Observation<'a, T, R>> for ObservationSet<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn from_iter<I>(observations: I) -> Self
+    where
+        I: IntoIterator<Item = Observation<'a, T, R>>,
+    {
+        Self {
+            observations: observations.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, T, R> Debug for ObservationSet<'a, T, R>
+where
+    T: 'a + ?Sized + Debug,
+    R: Recorder<&'a T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, R> ObservationSet<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self {
+            observations: Vec::new(),
+        }
+    }
+
+    /// Adds `observation` to the set, to be forgotten along with the rest.
+    pub fn insert(&mut self, observation: Observation<'a, T, R>) {
+        self.observations.push(observation);
+    }
+
+    /// Forgets every item in the set now, leaving it empty.
+    pub fn release_all(&mut self) {
+        self.observations.clear();
+    }
+
+    /// Rolls back every observation in the set, as if it had never been
+    /// made; see [`Recorder::undo_insert`].
+    pub(crate) fn undo_all(&mut self) {
+        for observation in self.observations.drain(..).rev() {
+            observation.undo();
+        }
+    }
+
+    /// Ends every observation in the set without forgetting the items. See
+    /// [`Observation::leak`].
+    pub fn leak(self) {
+        self.observations.into_iter().for_each(Observation::leak);
+    }
+
+    /// Returns the observed items, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.observations.iter().map(|observation| observation.item)
+    }
+
+    /// Returns the number of items in the set.
+    pub fn len(&self) -> usize {
+        self.observations.len()
+    }
+
+    /// Returns true if the set holds no observations.
+    pub fn is_empty(&self) -> bool {
+        self.observations.is_empty()
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Observes all of `items` at once, or none of them if any is already
+    /// observed or refused by the recorder, and returns a single guard that
+    /// forgets them all.
+    ///
+    /// Unlike noticing the items one by one, a failure leaves nothing
+    /// behind to clean up. An item that appears twice in `items` is noticed
+    /// twice, so with most recorders the second attempt fails the batch.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let _b = observer.notice("b");
+    /// assert!(observer.notice_all(["a", "b", "c"]).is_none());
+    /// assert!(observer.is_observing("b") && !observer.is_observing("a"));
+    ///
+    /// let ac = observer.notice_all(["a", "c"]).unwrap();
+    /// assert_eq!(ac.len(), 2);
+    /// drop(ac);
+    /// assert_eq!(observer.len(), 1);
+    /// ```
+    #[track_caller]
+    pub fn notice_all<I>(&self, items: I) -> Option<ObservationSet<'a, T, R>>
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        let items: Vec<&'a T> = items.into_iter().collect();
+        let entry = self.shared.entry();
+        let mut recorder = self.shared.recorder.borrow_mut();
+        // The items are observed in order, so each is noticed in the next
+        // generation.
+        if let Some(duplicate) = (entry.generation()..)
+            .zip(&items)
+            .position(|(generation, item)| !recorder.insert(item, entry.in_generation(generation)))
+        {
+            for item in &items[..duplicate] {
+                recorder.undo_insert(item);
+            }
+            drop(recorder);
+            self.shared.reject(&items[duplicate]);
+            return None;
+        }
+        drop(recorder);
+        Some(ObservationSet {
+            observations: items
+                .into_iter()
+                .map(|item| self.observation(item))
+                .collect(),
+        })
+    }
+
+    /// Observes whichever of `items` aren't already observed, in one pass,
+    /// and returns their observations along with the items that weren't
+    /// observed: duplicates, and any the recorder refused.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let _b = observer.notice("b");
+    /// let (claimed, requeue) = observer.notice_any(["a", "b", "c"]);
+    /// assert_eq!(claimed.iter().collect::<Vec<_>>(), ["a", "c"]);
+    /// assert_eq!(requeue, ["b"]);
+    /// ```
+    #[track_caller]
+    pub fn notice_any<I>(&self, items: I) -> (ObservationSet<'a, T, R>, Vec<&'a T>)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        let entry = self.shared.entry();
+        let (observed, duplicates): (Vec<&'a T>, Vec<&'a T>) = {
+            let mut recorder = self.shared.recorder.borrow_mut();
+            let mut generation = entry.generation();
+            items.into_iter().partition(|item| {
+                let inserted = recorder.insert(item, entry.in_generation(generation));
+                generation += u64::from(inserted);
+                inserted
+            })
+        };
+        for item in &duplicates {
+            self.shared.reject(item);
+        }
+        let observations = observed
+            .into_iter()
+            .map(|item| self.observation(item))
+            .collect();
+        (ObservationSet { observations }, duplicates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ObservationSet, Observer};
+
+    #[test]
+    fn notices_all_or_nothing() {
+        let o = Observer::new();
+        let g = o.notice(&3);
+        assert!(o.notice_all(&[1, 2, 3]).is_none());
+        assert!(o.notice_all(&[1, 2, 1]).is_none());
+        assert_eq!(o.len(), 1);
+        drop(g);
+        let all = o.notice_all(&[1, 2, 3]).unwrap();
+        assert_eq!(all.iter().collect::<Vec<_>>(), [&1, &2, &3]);
+        assert_eq!(o.active_count(), 3);
+        drop(all);
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn notice_any_claims_what_it_can() {
+        let o = Observer::new();
+        let _g = o.notice(&2);
+        let (claimed, duplicates) = o.notice_any(&[1, 2, 3, 1]);
+        assert_eq!(claimed.iter().collect::<Vec<_>>(), [&1, &3]);
+        assert_eq!(duplicates, [&2, &1]);
+        drop(claimed);
+        assert_eq!(o.len(), 1);
+    }
+
+    #[test]
+    fn batched_items_are_noticed_in_generations_of_their_own() {
+        let o = Observer::new();
+        let _g = o.notice(&2);
+        let _all = o.notice_all(&[5, 6]).unwrap();
+        let _any = o.notice_any(&[1, 2, 3]);
+        let generation = |item| o.shared.recorder.borrow()[&item].generation();
+        let generations: Vec<_> = [&2, &5, &6, &1, &3].into_iter().map(generation).collect();
+        assert_eq!(generations, [1, 2, 3, 4, 5]);
+        assert_eq!(o.stats().noticed(), 5);
+    }
+
+    #[test]
+    fn collects_and_releases_single_observations() {
+        let o = Observer::new();
+        let mut set: ObservationSet<_> = [1, 2].iter().filter_map(|i| o.notice(i)).collect();
+        set.extend(o.notice(&3));
+        set.insert(o.notice(&4).unwrap());
+        assert_eq!(format!("{:?}", set), "[1, 2, 3, 4]");
+        set.release_all();
+        assert!(o.is_empty());
+        set.insert(o.notice(&1).unwrap());
+        assert_eq!(o.len(), 1);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{Entry, Lookup, OwnedObservation, OwnedObserver, Recorder};
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+
+const BITS: usize = u64::BITS as usize;
+
+/// The keys a [`BitSet`] stores as bits, using up to 2 MiB.
+const DENSE_KEYS: usize = 1 << 24;
+
+/**
+BitSet is a [`Recorder`] for dense `usize` keys, such as graph node ids.
+
+It uses one bit per key up to the largest key recorded, growing as needed.
+Keys of 2<sup>24</sup> and up are instead kept in a `BTreeSet`, so that a
+stray large key can't make it allocate without bound.
+*/
+#[derive(Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+    sparse: BTreeSet<usize>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a set with room for keys below `capacity`, or 2<sup>24</sup>
+    /// if that is less, without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: vec![0; (capacity.min(DENSE_KEYS) + BITS - 1) / BITS],
+            sparse: BTreeSet::new(),
+        }
+    }
+
+    fn locate(key: usize) -> (usize, u64) {
+        (key / BITS, 1 << (key % BITS))
+    }
+}
+
+impl Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys = self.words.iter().enumerate().flat_map(|(word, bits)| {
+            (0..BITS)
+                .filter(move |bit| bits & (1 << bit) != 0)
+                .map(move |bit| word * BITS + bit)
+        });
+        f.debug_set()
+            .entries(keys.chain(self.sparse.iter().copied()))
+            .finish()
+    }
+}
+
+impl Recorder<usize> for BitSet {
+    fn insert(&mut self, item: usize, _entry: Entry) -> bool {
+        if item >= DENSE_KEYS {
+            return self.sparse.insert(item);
+        }
+        let (word, mask) = Self::locate(item);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        inserted
+    }
+
+    fn remove(&mut self, item: &usize) -> bool {
+        if *item >= DENSE_KEYS {
+            return self.sparse.remove(item);
+        }
+        let (word, mask) = Self::locate(*item);
+        match self.words.get_mut(word) {
+            Some(bits) if *bits & mask != 0 => {
+                *bits &= !mask;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn contains(&self, item: &usize) -> bool {
+        if *item >= DENSE_KEYS {
+            return self.sparse.contains(item);
+        }
+        let (word, mask) = Self::locate(*item);
+        self.words.get(word).is_some_and(|bits| bits & mask != 0)
+    }
+
+    fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|bits| bits.count_ones() as usize)
+            .sum::<usize>()
+            + self.sparse.len()
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+        self.sparse.clear();
+    }
+}
+
+impl Lookup<usize, usize> for BitSet {
+    fn contains_ref(&self, item: &usize) -> bool {
+        self.contains(item)
+    }
+}
+
+/**
+BitObserver is an [`OwnedObserver`] for `usize` keys backed by a [`BitSet`].
+
+When keys are small, dense indices, this is far faster and smaller than
+hashing them.
+```
+use forgetful::BitObserver;
+let observer = BitObserver::default();
+{
+    let observation = observer.notice(3).expect("never seen before");
+    assert!(observer.notice(3).is_none());
+}
+assert!(observer.notice(3).is_some());
+```
+*/
+pub type BitObserver = OwnedObserver<usize, BitSet>;
+
+/// The observation type of [`BitObserver`].
+pub type BitObservation = OwnedObservation<usize, BitSet>;
+
+impl OwnedObserver<usize, BitSet> {
+    /// Creates an observer with room for keys below `capacity`, or
+    /// 2<sup>24</sup> if that is less, without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_recorder(BitSet::with_capacity(capacity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::RefCell;
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = BitObserver::default();
+        let g = o.notice(70);
+        assert!(g.is_some());
+        assert!(o.notice(70).is_none());
+        assert!(o.notice(6).is_some());
+        drop(g);
+        assert!(o.notice(70).is_some());
+    }
+
+    #[test]
+    fn records_keys_across_word_boundaries() {
+        let mut s = BitSet::with_capacity(10);
+        for key in [0, 63, 64, 1000] {
+            assert!(Recorder::insert(&mut s, key, Entry::default()));
+            assert!(!Recorder::insert(&mut s, key, Entry::default()));
+        }
+        assert_eq!(format!("{:?}", s), "{0, 63, 64, 1000}");
+        assert!(Recorder::remove(&mut s, &64));
+        assert!(!Recorder::contains(&s, &64));
+        assert!(!Recorder::remove(&mut s, &5000));
+    }
+
+    #[test]
+    fn keeps_large_keys_sparsely() {
+        let o = BitObserver::with_capacity(usize::MAX);
+        let g = o.notice(usize::MAX).unwrap();
+        assert!(o.notice(usize::MAX).is_none());
+        let _small = o.notice(1).unwrap();
+        assert_eq!(o.len(), 2);
+        assert!(RefCell::borrow(&o.recorder).words.len() <= DENSE_KEYS / BITS);
+        drop(g);
+        assert!(!o.is_observing(&usize::MAX));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::hash::DefaultHashBuilder;
+use crate::{Entry, Lookup, Observation, Observer, Recorder};
+use alloc::borrow::Borrow;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash, Hasher};
+
+/**
+BloomRecorder is a [`Recorder`] backed by a counting Bloom filter.
+
+It uses a fixed amount of memory no matter how many items it records, at the
+cost of occasional false positives: an item that was never recorded may be
+reported as recorded, so [`Observer::notice`] may wrongly return `None`.
+It never produces false negatives, so an item that *is* observed is
+always detected.
+*/
+pub struct BloomRecorder<S = DefaultHashBuilder> {
+    counters: Vec<u32>,
+    hashes: u32,
+    len: usize,
+    hasher: S,
+}
+
+impl BloomRecorder {
+    /// Creates a filter sized so that, while up to `expected_items` are
+    /// recorded at once, the chance of a false positive stays around
+    /// `false_positive_rate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expected_items` is zero or `false_positive_rate` isn't
+    /// strictly between 0 and 1.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self::with_hasher(expected_items, false_positive_rate, Default::default())
+    }
+}
+
+impl<S> BloomRecorder<S>
+where
+    S: BuildHasher,
+{
+    /// Like [`BloomRecorder::new`], but using `hasher` to hash items.
+    pub fn with_hasher(expected_items: usize, false_positive_rate: f64, hasher: S) -> Self {
+        assert!(expected_items > 0, "expected_items must be positive");
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false_positive_rate must be between 0 and 1"
+        );
+        let ln2 = core::f64::consts::LN_2;
+        let n = expected_items as f64;
+        let m = (-n * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(1.0);
+        let k = (m / n * ln2).round().max(1.0);
+        Self {
+            counters: vec![0; m as usize],
+            hashes: k as u32,
+            len: 0,
+            hasher,
+        }
+    }
+
+    /// The indices of the counters for `item`, by double hashing.
+    fn indices<K: Hash + ?Sized>(&self, item: &K) -> impl Iterator<Item = usize> {
+        let mut hasher = self.hasher.build_hasher();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+        let (h1, h2) = (hash as u32 as u64, (hash >> 32) | 1);
+        let len = self.counters.len() as u64;
+        (0..self.hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+    }
+}
+
+impl<S> Debug for BloomRecorder<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BloomRecorder")
+            .field("counters", &self.counters.len())
+            .field("hashes", &self.hashes)
+            .finish()
+    }
+}
+
+impl<K, S> Recorder<K> for BloomRecorder<S>
+where
+    K: Hash,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, _entry: Entry) -> bool {
+        if self.contains(&item) {
+            return false;
+        }
+        for i in self.indices(&item) {
+            self.counters[i] = self.counters[i].saturating_add(1);
+        }
+        self.len += 1;
+        true
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        if !self.contains(item) {
+            return false;
+        }
+        for i in self.indices(item) {
+            // A saturated counter no longer knows how many items share it,
+            // so it has to stay set.
+            if self.counters[i] != u32::MAX {
+                self.counters[i] -= 1;
+            }
+        }
+        self.len -= 1;
+        true
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        self.indices(item).all(|i| self.counters[i] > 0)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.counters.fill(0);
+        self.len = 0;
+    }
+}
+
+impl<K, Q, S> Lookup<K, Q> for BloomRecorder<S>
+where
+    K: Hash + Borrow<Q>,
+    Q: Hash + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        self.indices(item).all(|i| self.counters[i] > 0)
+    }
+}
+
+/**
+BloomObserver is an [`Observer`] with bounded memory, backed by a
+[`BloomRecorder`].
+
+Because of false positives, it can occasionally report an item as already
+observed when it isn't. Use it where skipping an item now and then is
+acceptable, but unbounded memory isn't.
+```
+use forgetful::BloomObserver;
+let observer = BloomObserver::with_false_positive_rate(1000, 0.01);
+{
+    let observation = observer.notice("foo").expect("never seen before");
+    assert!(observer.notice("foo").is_none());
+}
+assert!(observer.notice("foo").is_some());
+```
+*/
+pub type BloomObserver<'a, T> = Observer<'a, T, BloomRecorder>;
+
+/// The observation type of [`BloomObserver`].
+pub type BloomObservation<'a, T> = Observation<'a, T, BloomRecorder>;
+
+impl<'a, T> Observer<'a, T, BloomRecorder>
+where
+    T: 'a + Hash + ?Sized,
+{
+    /// Creates an observer backed by [`BloomRecorder::new`].
+    pub fn with_false_positive_rate(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self::with_recorder(BloomRecorder::new(expected_items, false_positive_rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = BloomObserver::with_false_positive_rate(100, 0.01);
+        let g = o.notice(&1);
+        assert!(g.is_some());
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[test]
+    fn false_positive_rate_is_roughly_respected() {
+        let items: Vec<u32> = (0..2000).collect();
+        let o = BloomObserver::with_false_positive_rate(1000, 0.01);
+        let _held: Vec<_> = items[..1000].iter().map(|i| o.notice(i)).collect();
+        let false_positives = items[1000..]
+            .iter()
+            .filter(|i| o.notice(i).is_none())
+            .count();
+        assert!(false_positives < 50, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn shared_counters_survive_removal() {
+        // A tiny filter, so that items are sure to share counters.
+        let mut r = BloomRecorder::new(2, 0.5);
+        let inserted: Vec<u32> = (0..10)
+            .filter(|i| Recorder::insert(&mut r, *i, Entry::default()))
+            .collect();
+        let (last, rest) = inserted.split_last().unwrap();
+        for i in rest {
+            assert!(Recorder::remove(&mut r, i));
+        }
+        assert!(Recorder::contains(&r, last));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, Iterable, Lookup, Observation, Observer, Recorder};
+use core::cell::RefCell;
+use core::fmt::{self, Debug, Display};
+use core::hash::Hash;
+
+/// The error returned by [`BudgetedObserver::notice`] once the observer has
+/// used up its budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    budget: u64,
+}
+
+impl BudgetExceeded {
+    /// The number of items the observer was allowed to notice.
+    pub fn budget(&self) -> u64 {
+        self.budget
+    }
+}
+
+impl Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the budget of {} notices is used up", self.budget)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BudgetExceeded {}
+
+/**
+BudgetRecorder is a [`Recorder`] that wraps another, and refuses new items
+once it has recorded `budget` of them in total, however they were noticed.
+Forgetting items, or clearing the recorder, doesn't give the budget back,
+but [undoing](Recorder::undo_insert) an insert, as rollbacks do, does.
+*/
+#[derive(Clone, Debug)]
+pub struct BudgetRecorder<R> {
+    recorder: R,
+    budget: u64,
+    used: u64,
+}
+
+impl<R> BudgetRecorder<R> {
+    /// Creates a recorder that records up to `budget` items in `recorder`.
+    pub fn new(budget: u64, recorder: R) -> Self {
+        Self {
+            recorder,
+            budget,
+            used: 0,
+        }
+    }
+
+    /// Returns the number of items that may be recorded in total.
+    pub fn budget(&self) -> u64 {
+        self.budget
+    }
+
+    /// Returns the number of items that may still be recorded.
+    pub fn remaining(&self) -> u64 {
+        self.budget - self.used
+    }
+}
+
+impl<K, R> Recorder<K> for BudgetRecorder<R>
+where
+    R: Recorder<K>,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        if self.used == self.budget || !self.recorder.insert(item, entry) {
+            return false;
+        }
+        self.used += 1;
+        true
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        self.recorder.remove(item)
+    }
+
+    fn remove_all(&mut self, item: &K) -> bool {
+        self.recorder.remove_all(item)
+    }
+
+    fn undo_insert(&mut self, item: &K) -> bool {
+        // The insert never happened, so it doesn't count against the budget.
+        if !self.recorder.undo_insert(item) {
+            return false;
+        }
+        self.used -= 1;
+        true
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        self.recorder.contains(item)
+    }
+
+    fn can_insert(&self, item: &K) -> bool {
+        self.used < self.budget && self.recorder.can_insert(item)
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        self.recorder.entry(item)
+    }
+
+    fn len(&self) -> usize {
+        self.recorder.len()
+    }
+
+    fn clear(&mut self) {
+        self.recorder.clear()
+    }
+
+    fn reset_generations(&mut self) {
+        self.recorder.reset_generations()
+    }
+}
+
+impl<K, R> Iterable<K> for BudgetRecorder<R>
+where
+    R: Iterable<K>,
+{
+    type Iter<'r>
+        = R::Iter<'r>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.recorder.iter()
+    }
+}
+
+impl<K, Q, R> Lookup<K, Q> for BudgetRecorder<R>
+where
+    Q: ?Sized,
+    R: Lookup<K, Q>,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        self.recorder.contains_ref(item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        self.recorder.entry_ref(item)
+    }
+}
+
+/**
+BudgetedObserver is an [`Observer`] that may only notice so many items in
+total, after which noticing new ones fails with [`BudgetExceeded`]. This
+bounds the work of a traversal along with preventing repeats.
+```
+use forgetful::BudgetedObserver;
+let observer = BudgetedObserver::new(2);
+let _a = observer.notice("a").unwrap().expect("never seen before");
+drop(observer.notice("b"));
+assert!(observer.notice("a").unwrap().is_none());
+assert!(observer.notice("c").is_err());
+```
+The budget is kept by its [`BudgetRecorder`], so it counts every item the
+observer notices, but not duplicates, and also holds for items noticed
+through [`observer`](Self::observer), as children, in batches or by
+transfer: once it is used up, those are refused, as by a full recorder.
+*/
+pub struct BudgetedObserver<'a, T, R = HashMap<&'a T, Entry, DefaultHashBuilder>>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    observer: Observer<'a, T, BudgetRecorder<R>>,
+}
+
+impl<'a, T, R> Debug for BudgetedObserver<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+    Observer<'a, T, BudgetRecorder<R>>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.observer.fmt(f)
+    }
+}
+
+impl<'a, T> BudgetedObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Creates an observer that may notice `budget` items.
+    pub fn new(budget: u64) -> Self {
+        Self::with_recorder(budget, HashMap::default())
+    }
+}
+
+impl<'a, T, R> BudgetedObserver<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Creates an observer that may notice `budget` items, storing its
+    /// observations in `recorder`.
+    pub fn with_recorder(budget: u64, recorder: R) -> Self {
+        Self {
+            observer: Observer::with_recorder(BudgetRecorder::new(budget, recorder)),
+        }
+    }
+
+    /// Observes `item`, returning `Ok(None)` if it is already observed, or
+    /// [`BudgetExceeded`] if it isn't but the budget is used up.
+    ///
+    /// An item the wrapped recorder refuses while budget remains, e.g. for
+    /// lack of room in a [`WeightedRecorder`](crate::WeightedRecorder), also
+    /// gives `Ok(None)`. To tell the two apart, notice through
+    /// [`observer`](Self::observer) with
+    /// [`notice_checked`](Observer::notice_checked), which reports it as
+    /// [`NoticeError::Refused`](crate::NoticeError::Refused).
+    #[track_caller]
+    pub fn notice(
+        &self,
+        item: &'a T,
+    ) -> Result<Option<Observation<'a, T, BudgetRecorder<R>>>, BudgetExceeded> {
+        if self.remaining() == 0 && !self.observer.observes(item) {
+            return Err(BudgetExceeded {
+                budget: self.budget(),
+            });
+        }
+        Ok(self.observer.notice(item))
+    }
+
+    /// Returns the number of items that may be noticed in total.
+    pub fn budget(&self) -> u64 {
+        RefCell::borrow(&self.observer.shared.recorder).budget()
+    }
+
+    /// Returns the number of items that may still be noticed.
+    pub fn remaining(&self) -> u64 {
+        RefCell::borrow(&self.observer.shared.recorder).remaining()
+    }
+
+    /// Returns the underlying observer, whose notices also count against
+    /// the budget.
+    pub fn observer(&self) -> &Observer<'a, T, BudgetRecorder<R>> {
+        &self.observer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fails_once_the_budget_is_used_up() {
+        let o = BudgetedObserver::new(3);
+        let g = o.notice(&1).unwrap().unwrap();
+        let _child = g.notice_child(&2).unwrap();
+        assert!(o.notice(&1).unwrap().is_none());
+        assert_eq!(o.remaining(), 1);
+        drop(o.notice(&3));
+        assert_eq!(o.notice(&4).unwrap_err().budget(), 3);
+        drop(g);
+        assert!(o.notice(&1).is_err());
+    }
+
+    #[test]
+    fn refusals_by_the_wrapped_recorder_are_not_duplicates() {
+        use crate::{NoticeError, WeightedRecorder};
+
+        let o = BudgetedObserver::with_recorder(10, WeightedRecorder::new(1));
+        let _a = o.notice(&1).unwrap().unwrap();
+        assert!(o.notice(&2).unwrap().is_none());
+        assert_eq!(
+            o.observer().notice_checked(&2).unwrap_err(),
+            NoticeError::Refused(&2)
+        );
+        assert_eq!(o.remaining(), 9);
+    }
+
+    #[test]
+    fn rolled_back_notices_are_not_counted() {
+        let o = BudgetedObserver::new(2);
+        let _a = o.notice(&1).unwrap().unwrap();
+        assert!(o.observer().notice_all([&2, &1]).is_none());
+        let mut t = o.observer().begin();
+        assert!(t.notice(&3));
+        t.rollback();
+        assert_eq!(o.remaining(), 1);
+    }
+
+    #[test]
+    fn every_way_of_noticing_counts_against_the_budget() {
+        let o = BudgetedObserver::new(2);
+        let g = o.observer().notice(&1).unwrap();
+        let _child = g.notice_child(&2).unwrap();
+        assert_eq!(o.remaining(), 0);
+        assert!(g.notice_child(&3).is_none());
+        assert!(o.observer().scope().notice(&3).is_none());
+        assert!(o.observer().notice_all([&3, &4]).is_none());
+        let other = Observer::new();
+        let stray = other.notice(&5).unwrap();
+        assert!(stray.transfer(o.observer()).is_err());
+        assert_eq!(o.observer().stats().duplicates(), 0);
+        assert_eq!(o.notice(&3).unwrap_err().budget(), 2);
+    }
+}
@@ -0,0 +1,321 @@
+use crate::hash::{hash_map, DefaultHashBuilder, HashMap};
+use crate::{Entry, Iterable, Lookup, Observation, Observer, Recorder};
+use alloc::borrow::Borrow;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::Map;
+use core::marker::PhantomData;
+
+/// A way of ignoring case when comparing strings, for a [`CaselessRecorder`].
+pub trait CaseFold {
+    /// Returns true if `a` and `b` are equal, ignoring case.
+    fn eq(a: &str, b: &str) -> bool;
+
+    /// Feeds `s` into `state`, ignoring case, so that strings that are
+    /// [`eq`](Self::eq) hash alike.
+    fn hash<H: Hasher>(s: &str, state: &mut H);
+}
+
+/// Ignores the case of ASCII letters only, like
+/// [`str::eq_ignore_ascii_case`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ascii;
+
+impl CaseFold for Ascii {
+    fn eq(a: &str, b: &str) -> bool {
+        a.eq_ignore_ascii_case(b)
+    }
+
+    fn hash<H: Hasher>(s: &str, state: &mut H) {
+        for byte in s.bytes() {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+        // Like `str`'s own Hash, end with a byte no string contains.
+        state.write_u8(0xff);
+    }
+}
+
+/// Ignores case by comparing the strings' lowercase mappings, as with
+/// [`char::to_lowercase`]. Available with the `unicode-case` feature.
+#[cfg(feature = "unicode-case")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Unicode;
+
+#[cfg(feature = "unicode-case")]
+impl CaseFold for Unicode {
+    fn eq(a: &str, b: &str) -> bool {
+        a.chars()
+            .flat_map(char::to_lowercase)
+            .eq(b.chars().flat_map(char::to_lowercase))
+    }
+
+    fn hash<H: Hasher>(s: &str, state: &mut H) {
+        for c in s.chars().flat_map(char::to_lowercase) {
+            state.write_u32(c.into());
+        }
+        // Not a char, so it can't be confused with one.
+        state.write_u32(u32::MAX);
+    }
+}
+
+/// A string that compares and hashes ignoring case. It is public only so that
+/// it can appear in [`CaselessRecorder`]'s iterator type.
+pub struct Folded<'a, F> {
+    item: &'a str,
+    fold: PhantomData<F>,
+}
+
+impl<'a, F> Folded<'a, F> {
+    fn new(item: &'a str) -> Self {
+        Self {
+            item,
+            fold: PhantomData,
+        }
+    }
+}
+
+impl<F: CaseFold> PartialEq for Folded<'_, F> {
+    fn eq(&self, other: &Self) -> bool {
+        F::eq(self.item, other.item)
+    }
+}
+
+impl<F: CaseFold> Eq for Folded<'_, F> {}
+
+impl<F: CaseFold> Hash for Folded<'_, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        F::hash(self.item, state)
+    }
+}
+
+/// Something a [`Folded`] can be looked up by: it, or a plain string. Lookups
+/// go through it so that the string needn't live as long as the recorder's
+/// items. It is public only so that it can appear in `Folded`'s `Borrow`
+/// impl.
+pub trait FoldKey<F> {
+    /// Returns the string to compare and hash, ignoring case.
+    fn key(&self) -> &str;
+}
+
+impl<F> FoldKey<F> for Folded<'_, F> {
+    fn key(&self) -> &str {
+        self.item
+    }
+}
+
+impl<F> FoldKey<F> for &str {
+    fn key(&self) -> &str {
+        self
+    }
+}
+
+impl<F: CaseFold> PartialEq for dyn FoldKey<F> + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        F::eq(self.key(), other.key())
+    }
+}
+
+impl<F: CaseFold> Eq for dyn FoldKey<F> + '_ {}
+
+impl<F: CaseFold> Hash for dyn FoldKey<F> + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        F::hash(self.key(), state)
+    }
+}
+
+impl<'a, 'k, F> Borrow<dyn FoldKey<F> + 'k> for Folded<'a, F>
+where
+    'a: 'k,
+    F: 'k,
+{
+    fn borrow(&self) -> &(dyn FoldKey<F> + 'k) {
+        self
+    }
+}
+
+/**
+CaselessRecorder is a [`Recorder`] of strings that ignores their case, as
+`F` says, without allocating folded copies of them.
+
+The recorder keeps the spelling each item was first noticed with.
+*/
+pub struct CaselessRecorder<'a, F = Ascii, S = DefaultHashBuilder> {
+    items: HashMap<Folded<'a, F>, Entry, S>,
+}
+
+impl<'a, F, S> Default for CaselessRecorder<'a, F, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self {
+            items: HashMap::default(),
+        }
+    }
+}
+
+impl<'a, F, S> Debug for CaselessRecorder<'a, F, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.items.keys().map(|folded| folded.item))
+            .finish()
+    }
+}
+
+impl<'a, F, S> Recorder<&'a str> for CaselessRecorder<'a, F, S>
+where
+    F: CaseFold,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: &'a str, entry: Entry) -> bool {
+        match HashMap::entry(&mut self.items, Folded::new(item)) {
+            hash_map::Entry::Occupied(_) => false,
+            hash_map::Entry::Vacant(vacant) => {
+                vacant.insert(entry);
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, item: &&'a str) -> bool {
+        self.items.remove(&Folded::new(item)).is_some()
+    }
+
+    fn contains(&self, item: &&'a str) -> bool {
+        self.items.contains_key(&Folded::new(item))
+    }
+
+    fn entry(&self, item: &&'a str) -> Option<&Entry> {
+        self.items.get(&Folded::new(item))
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn clear(&mut self) {
+        self.items.clear()
+    }
+
+    fn reset_generations(&mut self) {
+        for entry in self.items.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+impl<'a, F, S> Iterable<&'a str> for CaselessRecorder<'a, F, S>
+where
+    F: CaseFold,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = Map<hash_map::Keys<'r, Folded<'a, F>, Entry>, fn(&'r Folded<'a, F>) -> &'r &'a str>
+    where
+        Self: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.items.keys().map(|folded| &folded.item)
+    }
+}
+
+impl<'a, F, S> Lookup<&'a str, str> for CaselessRecorder<'a, F, S>
+where
+    F: CaseFold,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &str) -> bool {
+        self.items.contains_key(&item as &dyn FoldKey<F>)
+    }
+
+    fn entry_ref(&self, item: &str) -> Option<&Entry> {
+        self.items.get(&item as &dyn FoldKey<F>)
+    }
+}
+
+/**
+CaselessObserver is an [`Observer`] of strings that ignores ASCII case, as
+suits hostnames and HTTP header names.
+```
+use forgetful::CaselessObserver;
+let observer = CaselessObserver::default();
+let _host = observer.notice("Example.COM").expect("never seen before");
+assert!(observer.notice("example.com").is_none());
+assert_eq!(observer.snapshot(), ["Example.COM"]);
+```
+*/
+pub type CaselessObserver<'a> = Observer<'a, str, CaselessRecorder<'a>>;
+
+/// The observation type of [`CaselessObserver`].
+pub type CaselessObservation<'a> = Observation<'a, str, CaselessRecorder<'a>>;
+
+/**
+UnicodeCaselessObserver is an [`Observer`] of strings that ignores case by
+Unicode's rules, rather than just ASCII's. It is available with the
+`unicode-case` feature.
+```
+use forgetful::UnicodeCaselessObserver;
+let observer = UnicodeCaselessObserver::default();
+let _city = observer.notice("ZÜRICH").expect("never seen before");
+assert!(observer.notice("zürich").is_none());
+```
+*/
+#[cfg(feature = "unicode-case")]
+pub type UnicodeCaselessObserver<'a> = Observer<'a, str, CaselessRecorder<'a, Unicode>>;
+
+/// The observation type of [`UnicodeCaselessObserver`].
+#[cfg(feature = "unicode-case")]
+pub type UnicodeCaselessObservation<'a> = Observation<'a, str, CaselessRecorder<'a, Unicode>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_ascii_case() {
+        let o = CaselessObserver::default();
+        let g = o.notice("Content-Type").unwrap();
+        assert!(o.is_observing("content-type"));
+        assert!(o.notice("CONTENT-TYPE").is_none());
+        assert!(o.notice("Content-Length").is_some());
+        drop(g);
+        assert!(o.notice("content-type").is_some());
+    }
+
+    #[test]
+    fn looks_up_entries_by_short_lived_strings() {
+        let mut recorder = CaselessRecorder::<Ascii>::default();
+        assert!(recorder.insert("Host", Entry::default().in_generation(7)));
+        let query = String::from("HOST");
+        assert_eq!(
+            recorder.entry_ref(query.as_str()).map(Entry::generation),
+            Some(7)
+        );
+        assert!(recorder.entry_ref("Accept").is_none());
+    }
+
+    #[cfg(feature = "timestamps")]
+    #[test]
+    fn knows_when_items_were_noticed() {
+        let o = CaselessObserver::default();
+        let _g = o.notice("Host").unwrap();
+        assert!(o.observed_since("host").is_some());
+    }
+
+    #[test]
+    fn ascii_folding_leaves_other_letters_alone() {
+        let o = CaselessObserver::default();
+        let _g = o.notice("Ä").unwrap();
+        assert!(o.notice("ä").is_some());
+    }
+
+    #[cfg(feature = "unicode-case")]
+    #[test]
+    fn ignores_unicode_case() {
+        let o = UnicodeCaselessObserver::default();
+        let _g = o.notice("ΣΊΣΥΦΟΣ").unwrap();
+        assert!(o.notice("σίσυφοσ").is_none());
+        let _a = o.notice("Ä").unwrap();
+        assert!(o.notice("ä").is_none());
+    }
+}
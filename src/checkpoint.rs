@@ -0,0 +1,130 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, Observer, Recorder, Shared};
+use alloc::rc::Rc;
+use core::fmt::{self, Debug};
+
+/// A point in an [`Observer`]'s history to roll back to, made by
+/// [`Observer::checkpoint`].
+///
+/// The observer only keeps a log of its observations while marks are alive,
+/// so drop them once they are no longer needed.
+pub struct Mark<'a, T, R = HashMap<&'a T, Entry, DefaultHashBuilder>>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// The sequence number of the first observation made after the mark.
+    sequence: u64,
+    shared: Rc<Shared<&'a T, R>>,
+}
+
+impl<'a, T, R> Debug for Mark<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Mark").field(&self.sequence).finish()
+    }
+}
+
+impl<'a, T, R> Drop for Mark<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn drop(&mut self) {
+        let marks = self.shared.marks.get() - 1;
+        self.shared.marks.set(marks);
+        if marks == 0 {
+            self.shared.log.borrow_mut().clear();
+        }
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Marks the current point in the observer's history, so that
+    /// [`rollback_to`](Self::rollback_to) can undo what follows.
+    pub fn checkpoint(&self) -> Mark<'a, T, R> {
+        self.shared.marks.set(self.shared.marks.get() + 1);
+        Mark {
+            sequence: self.shared.sequence.get(),
+            shared: Rc::clone(&self.shared),
+        }
+    }
+
+    /// Forgets every item noticed since `mark` was made, newest first,
+    /// including those whose guards are still alive; dropping such a guard
+    /// later does nothing. Observations made before the mark are untouched,
+    /// and the mark can be rolled back to again.
+    ///
+    /// This suits backtracking searches, which unwind visited states in
+    /// bulk rather than one guard at a time.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let _root = observer.notice("root");
+    /// let decided = observer.checkpoint();
+    /// let mut assigned = vec![observer.notice("x=1"), observer.notice("y=0")];
+    /// observer.rollback_to(&decided);
+    /// assert_eq!(observer.len(), 1);
+    /// assigned.push(observer.notice("x=0"));
+    /// observer.rollback_to(&decided);
+    /// assert!(observer.is_observing("root") && observer.len() == 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mark` was made by a different observer.
+    pub fn rollback_to(&self, mark: &Mark<'a, T, R>) {
+        assert!(
+            Rc::ptr_eq(&self.shared, &mark.shared),
+            "rolled back to a mark of another observer"
+        );
+        let undone = {
+            let mut log = self.shared.log.borrow_mut();
+            let start = log.partition_point(|(sequence, _)| *sequence < mark.sequence);
+            log.split_off(start)
+        };
+        for (_, child) in undone.into_iter().rev() {
+            self.shared.sweep_child(child, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn rolls_back_nested_marks() {
+        let o = Observer::new();
+        let first = o.checkpoint();
+        let a = o.notice(&1).unwrap();
+        let child = a.notice_child(&2).unwrap();
+        let second = o.checkpoint();
+        let scope = o.scope();
+        let _b = scope.notice(&3).unwrap();
+        drop(child);
+        let _c = o.notice(&2).unwrap();
+        o.rollback_to(&second);
+        assert_eq!(o.len(), 1);
+        let _d = o.notice(&4).unwrap();
+        o.rollback_to(&first);
+        assert!(o.is_empty());
+        drop((first, second, scope, a));
+        assert!(o.shared.log.borrow().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "another observer")]
+    fn rejects_marks_of_other_observers() {
+        let (a, b) = (Observer::<u8>::new(), Observer::new());
+        a.rollback_to(&b.checkpoint());
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{Iterable, Lookup, Observation, Observer, Recorder};
+
+/**
+A combination of two observers that treats an item as observed if either
+of them observes it, made by [`Observer::union`].
+
+Noticing an item records it in the first observer; the second only acts as
+a filter. This layers policies, e.g. permanently banned items on top of
+the ones currently in flight.
+```
+use forgetful::Observer;
+let banned = Observer::with_blocked(["spam.example"]);
+let in_flight = Observer::new();
+let allowed = in_flight.union(&banned);
+let _fetching = allowed.notice("rust-lang.org").unwrap();
+assert!(allowed.notice("rust-lang.org").is_none());
+assert!(allowed.notice("spam.example").is_none());
+assert!(!in_flight.is_observing("spam.example"));
+```
+*/
+pub struct Union<'o, 'a, T, R, S>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+    S: Recorder<&'a T>,
+{
+    first: &'o Observer<'a, T, R>,
+    second: &'o Observer<'a, T, S>,
+}
+
+/**
+A combination of two observers that only treats an item as observed if
+both of them observe it, made by [`Observer::intersection`].
+
+Noticing an item records it in the first observer if it can, and otherwise
+in the second.
+```
+use forgetful::Observer;
+let (left, right) = (Observer::new(), Observer::new());
+let either = left.intersection(&right);
+let _first = either.notice("job").unwrap();
+let _second = either.notice("job").unwrap();
+assert!(left.is_observing("job") && right.is_observing("job"));
+assert!(either.notice("job").is_none());
+```
+*/
+pub struct Intersection<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    first: &'o Observer<'a, T, R>,
+    second: &'o Observer<'a, T, R>,
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Combines this observer with `other` into a [`Union`].
+    pub fn union<'o, S>(&'o self, other: &'o Observer<'a, T, S>) -> Union<'o, 'a, T, R, S>
+    where
+        S: Recorder<&'a T>,
+    {
+        Union {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Combines this observer with `other` into an [`Intersection`].
+    pub fn intersection<'o>(&'o self, other: &'o Self) -> Intersection<'o, 'a, T, R> {
+        Intersection {
+            first: self,
+            second: other,
+        }
+    }
+}
+
+/// Observers are equal if they are currently observing the same items,
+/// whatever their recorders.
+///
+/// ```
+/// use forgetful::{Observer, OrdObserver};
+/// let (bfs, dfs) = (Observer::new(), OrdObserver::default());
+/// let _a = (bfs.notice("a"), dfs.notice("a"));
+/// let _b = (bfs.notice("b"), dfs.notice("b"));
+/// assert!(bfs == dfs);
+/// ```
+impl<'a, T, R, S> PartialEq<Observer<'a, T, S>> for Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Iterable<&'a T>,
+    S: Recorder<&'a T>,
+{
+    fn eq(&self, other: &Observer<'a, T, S>) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+impl<'a, T, R> Eq for Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Iterable<&'a T>,
+{
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Iterable<&'a T>,
+{
+    /// Returns true if none of the items observed here are observed by
+    /// `other`.
+    pub fn is_disjoint<S>(&self, other: &Observer<'a, T, S>) -> bool
+    where
+        S: Recorder<&'a T>,
+    {
+        !self.observed().iter().any(|item| other.observes(item))
+    }
+
+    /// Returns true if every item observed here is also observed by
+    /// `other`.
+    pub fn is_subset<S>(&self, other: &Observer<'a, T, S>) -> bool
+    where
+        S: Recorder<&'a T>,
+    {
+        self.observed().iter().all(|item| other.observes(item))
+    }
+}
+
+impl<'o, 'a, T, R, S> Union<'o, 'a, T, R, S>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+    S: Recorder<&'a T>,
+{
+    /// Notices `item` in the first observer, unless either observer already
+    /// observes it.
+    #[track_caller]
+    pub fn notice(&self, item: &'a T) -> Option<Observation<'a, T, R>> {
+        if self.second.observes(item) {
+            None
+        } else {
+            self.first.notice(item)
+        }
+    }
+
+    /// Returns true if either observer observes `item`.
+    pub fn is_observing(&self, item: &T) -> bool
+    where
+        R: Lookup<&'a T, T>,
+        S: Lookup<&'a T, T>,
+    {
+        self.first.is_observing(item) || self.second.is_observing(item)
+    }
+}
+
+impl<'o, 'a, T, R> Intersection<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Notices `item` in the first observer, or in the second if the first
+    /// already observes it. Returns None if both do.
+    #[track_caller]
+    pub fn notice(&self, item: &'a T) -> Option<Observation<'a, T, R>> {
+        match self.first.notice(item) {
+            Some(observation) => Some(observation),
+            None => self.second.notice(item),
+        }
+    }
+
+    /// Returns true if both observers observe `item`.
+    pub fn is_observing(&self, item: &T) -> bool
+    where
+        R: Lookup<&'a T, T>,
+    {
+        self.first.is_observing(item) && self.second.is_observing(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Observer, OrdObserver};
+
+    #[test]
+    fn union_filters_by_either_observer() {
+        let (a, b) = (Observer::new(), OrdObserver::default());
+        let _held = b.notice(&1);
+        let union = a.union(&b);
+        assert!(union.is_observing(&1));
+        assert!(union.notice(&1).is_none());
+        let g = union.notice(&2).unwrap();
+        assert!(a.is_observing(&2) && !b.is_observing(&2));
+        drop(g);
+        assert!(!union.is_observing(&2));
+    }
+
+    #[test]
+    fn intersection_fails_only_if_both_observe() {
+        let (a, b) = (Observer::new(), Observer::new());
+        let _held = b.notice(&1);
+        let both = a.intersection(&b);
+        assert!(!both.is_observing(&1));
+        let _g = both.notice(&1).unwrap();
+        assert!(both.is_observing(&1));
+        assert!(both.notice(&1).is_none());
+    }
+
+    #[test]
+    fn compares_observed_items() {
+        let (a, b) = (Observer::new(), OrdObserver::default());
+        assert!(a == b && a.is_disjoint(&b) && a.is_subset(&b));
+        let _a1 = a.notice(&1);
+        let _b2 = b.notice(&2);
+        assert!(a != b && a.is_disjoint(&b));
+        let _b1 = b.notice(&1);
+        assert!(a.is_subset(&b) && !b.is_subset(&a) && !a.is_disjoint(&b));
+        assert_eq!(a, a.clone());
+    }
+}
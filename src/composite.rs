@@ -0,0 +1,58 @@
+use crate::{Iterable, Observer};
+use alloc::vec::Vec;
+
+/// Observers of pairs, such as `(tenant, resource)` keys, can be queried and
+/// cleaned up by the first half of the key.
+///
+/// ```
+/// use forgetful::Observer;
+/// let keys = [("acme", 1), ("acme", 2), ("globex", 1)];
+/// let observer = Observer::new();
+/// let _held: Vec<_> = keys.iter().map(|key| observer.notice(key)).collect();
+/// assert_eq!(observer.count_with_first(&"acme"), 2);
+/// observer.forget_where_first(&"acme");
+/// assert_eq!(observer.observed_with_first(&"acme"), Vec::<&(&str, i32)>::new());
+/// assert!(observer.is_observing(&("globex", 1)));
+/// ```
+impl<'a, A, B, R> Observer<'a, (A, B), R>
+where
+    A: 'a + PartialEq,
+    B: 'a,
+    R: Iterable<&'a (A, B)>,
+{
+    /// Returns the observed items whose first half is `first`.
+    pub fn observed_with_first(&self, first: &A) -> Vec<&'a (A, B)> {
+        self.observed().iter().filter(|(a, _)| a == first).collect()
+    }
+
+    /// Returns the number of observed items whose first half is `first`.
+    pub fn count_with_first(&self, first: &A) -> usize {
+        self.observed().iter().filter(|(a, _)| a == first).count()
+    }
+
+    /// Forgets every item whose first half is `first`. As with
+    /// [`retain`](Observer::retain), outstanding observations of them stay
+    /// armed.
+    pub fn forget_where_first(&self, first: &A) {
+        self.retain(|(a, _)| a != first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn queries_by_first_half() {
+        let keys = [(1, 'a'), (1, 'b'), (2, 'a')];
+        let o = Observer::new();
+        let _held: alloc::vec::Vec<_> = keys.iter().map(|key| o.notice(key)).collect();
+        let mut tenant = o.observed_with_first(&1);
+        tenant.sort();
+        assert_eq!(tenant, [&(1, 'a'), &(1, 'b')]);
+        assert_eq!(o.count_with_first(&3), 0);
+        o.forget_where_first(&1);
+        assert_eq!(o.len(), 1);
+        assert!(o.notice(&(1, 'a')).is_some());
+    }
+}
@@ -0,0 +1,130 @@
+use dashmap::DashSet;
+use std::cmp::Eq;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// An observation produced by [`ConcurrentObserver::notice`].
+pub struct ConcurrentObservation<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    item: &'a T,
+    recorder: Arc<DashSet<&'a T>>,
+}
+
+impl<'a, T> Debug for ConcurrentObservation<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.item)
+    }
+}
+
+impl<'a, T> Drop for ConcurrentObservation<'a, T>
+where
+    T: Eq + Hash + ?Sized,
+{
+    fn drop(&mut self) {
+        self.recorder.remove(self.item);
+    }
+}
+
+/**
+ConcurrentObserver is a thread-safe observer backed by a sharded
+[`DashSet`], available with the `dashmap` feature.
+
+Unlike [`SyncObserver`](crate::SyncObserver), which guards its set with a
+single mutex, items are spread across independently locked shards, so
+threads noticing different items rarely contend with each other.
+```
+use forgetful::ConcurrentObserver;
+let observer = ConcurrentObserver::new();
+{
+    let observation = observer.notice("foo").expect("never seen before");
+    assert!(observer.notice("foo").is_none());
+}
+assert!(observer.notice("foo").is_some());
+```
+*/
+pub struct ConcurrentObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    recorder: Arc<DashSet<&'a T>>,
+}
+
+impl<'a, T> Default for ConcurrentObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> Debug for ConcurrentObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.recorder.fmt(f)
+    }
+}
+
+impl<'a, T> ConcurrentObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    pub fn new() -> Self {
+        Self {
+            recorder: Default::default(),
+        }
+    }
+
+    pub fn notice(&self, item: &'a T) -> Option<ConcurrentObservation<'a, T>> {
+        // DashSet::insert takes the shard's write lock, so this is atomic
+        // with respect to other notices of the same item.
+        if self.recorder.insert(item) {
+            Some(ConcurrentObservation {
+                item,
+                recorder: Arc::clone(&self.recorder),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = ConcurrentObserver::new();
+        let g = o.notice(&1);
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[test]
+    fn each_item_is_observed_by_exactly_one_thread() {
+        let items: &'static [u32] = Box::leak((0..64).collect());
+        let o = Arc::new(ConcurrentObserver::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let o = Arc::clone(&o);
+                thread::spawn(move || items.iter().filter_map(|i| o.notice(i)).collect::<Vec<_>>())
+            })
+            .collect();
+        // Keep every observation alive until all threads are done, so each
+        // item can be won at most once.
+        let won: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        let won: usize = won.iter().map(Vec::len).sum();
+        assert_eq!(won, items.len());
+    }
+}
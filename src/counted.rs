@@ -0,0 +1,267 @@
+use crate::hash::{hash_map, DefaultHashBuilder, HashMap};
+use crate::{Entry, Iterable, Lookup, Observation, Observer, Recorder};
+use alloc::borrow::Borrow;
+use core::cell::RefCell;
+use core::cmp::Eq;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash};
+
+/**
+CountedRecorder is a [`Recorder`] that lets each item be recorded up to a
+fixed number of times at once, like a semaphore per item.
+
+Each insert takes one of the item's permits and each removal returns one.
+The item counts as recorded while any of its permits are taken, and is
+forgotten once all of them are returned.
+*/
+pub struct CountedRecorder<K, S = DefaultHashBuilder> {
+    permits: usize,
+    /// The permits taken for each item, with the entry of its first
+    /// observation.
+    items: HashMap<K, (usize, Entry), S>,
+}
+
+impl<K> CountedRecorder<K> {
+    /// Creates a recorder allowing `permits` concurrent records of each item.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `permits` is zero.
+    pub fn new(permits: usize) -> Self {
+        Self::with_hasher(permits, Default::default())
+    }
+}
+
+impl<K, S> CountedRecorder<K, S> {
+    /// Like [`CountedRecorder::new`], but using `hasher` to hash items.
+    pub fn with_hasher(permits: usize, hasher: S) -> Self {
+        assert!(permits > 0, "permits must be positive");
+        Self {
+            permits,
+            items: HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// Returns the number of times each item may be recorded at once.
+    pub fn permits(&self) -> usize {
+        self.permits
+    }
+}
+
+impl<K, S> CountedRecorder<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the number of permits currently taken for `item`, which may
+    /// be any borrowed form of the item type.
+    pub fn count<Q>(&self, item: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.items.get(item).map_or(0, |(count, _)| *count)
+    }
+}
+
+impl<K, S> Debug for CountedRecorder<K, S>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.items.iter().map(|(item, (count, _))| (item, count)))
+            .finish()
+    }
+}
+
+impl<K, S> Recorder<K> for CountedRecorder<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        match self.items.entry(item) {
+            hash_map::Entry::Occupied(mut occupied) => {
+                let (count, _) = occupied.get_mut();
+                if *count == self.permits {
+                    return false;
+                }
+                *count += 1;
+            }
+            hash_map::Entry::Vacant(vacant) => {
+                vacant.insert((1, entry));
+            }
+        }
+        true
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        match self.items.get_mut(item) {
+            Some((count, _)) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.items.remove(item);
+            }
+            None => return false,
+        }
+        true
+    }
+
+    fn remove_all(&mut self, item: &K) -> bool {
+        self.items.remove(item).is_some()
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        self.items.contains_key(item)
+    }
+
+    fn can_insert(&self, item: &K) -> bool {
+        self.count(item) < self.permits
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        self.items.get(item).map(|(_, entry)| entry)
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn clear(&mut self) {
+        self.items.clear()
+    }
+
+    fn reset_generations(&mut self) {
+        for (_, entry) in self.items.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+impl<K, S> Iterable<K> for CountedRecorder<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = hash_map::Keys<'r, K, (usize, Entry)>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.items.keys()
+    }
+}
+
+impl<K, Q, S> Lookup<K, Q> for CountedRecorder<K, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        self.items.contains_key(item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        self.items.get(item).map(|(_, entry)| entry)
+    }
+}
+
+/**
+CountedObserver is an [`Observer`] that allows each item to be observed a
+limited number of times at once, backed by a [`CountedRecorder`].
+
+Rather than deduplicating, this bounds concurrency: e.g. at most two
+in-flight fetches per host.
+```
+use forgetful::CountedObserver;
+let observer = CountedObserver::with_permits(2);
+let first = observer.notice("example.com").expect("a permit is free");
+let second = observer.notice("example.com").expect("a permit is free");
+assert!(observer.notice("example.com").is_none());
+drop(first);
+assert!(observer.notice("example.com").is_some());
+```
+*/
+pub type CountedObserver<'a, T> = Observer<'a, T, CountedRecorder<&'a T>>;
+
+/// The observation type of [`CountedObserver`].
+pub type CountedObservation<'a, T> = Observation<'a, T, CountedRecorder<&'a T>>;
+
+impl<'a, T> Observer<'a, T, CountedRecorder<&'a T>>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Creates an observer backed by [`CountedRecorder::new`].
+    pub fn with_permits(permits: usize) -> Self {
+        Self::with_recorder(CountedRecorder::new(permits))
+    }
+}
+
+impl<'a, T, S> Observer<'a, T, CountedRecorder<&'a T, S>>
+where
+    T: 'a + Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    /// Returns the number of outstanding observations of `item`.
+    pub fn count(&self, item: &T) -> usize {
+        RefCell::borrow(&self.shared.recorder).count(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_up_to_permits_observations() {
+        let o = CountedObserver::with_permits(3);
+        let guards: Vec<_> = (0..3).map(|_| o.notice(&1).unwrap()).collect();
+        assert!(o.notice(&1).is_none());
+        assert!(o.notice(&2).is_some());
+        assert_eq!(o.count(&1), 3);
+        assert_eq!(o.len(), 1);
+        drop(guards);
+        assert_eq!(o.count(&1), 0);
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn retain_forgets_every_permit() {
+        let o = CountedObserver::with_permits(3);
+        let guards: Vec<_> = (0..2).map(|_| o.notice(&1).unwrap()).collect();
+        o.retain(|_| false);
+        assert!(!o.is_observing(&1));
+        let fresh = o.notice(&1).unwrap();
+        drop(guards);
+        assert_eq!(o.count(&1), 1);
+        drop(fresh);
+
+        let keys = [(1, 'a'), (2, 'a')];
+        let o = Observer::with_recorder(CountedRecorder::new(2));
+        let _held: Vec<_> = keys
+            .iter()
+            .chain(&keys)
+            .map(|key| o.notice(key).unwrap())
+            .collect();
+        o.forget_where_first(&1);
+        assert!(!o.is_observing(&(1, 'a')));
+        assert!(o.is_observing(&(2, 'a')));
+    }
+
+    #[test]
+    fn owned_observer_respects_permits() {
+        let o = crate::OwnedObserver::with_recorder(CountedRecorder::new(2));
+        let _g1 = o.notice("a".to_string()).unwrap();
+        let _g2 = o.notice("a".to_string()).unwrap();
+        assert!(o.notice("a".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_without_permits() {
+        CountedObserver::<u32>::with_permits(0);
+    }
+}
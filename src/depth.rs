@@ -0,0 +1,294 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{
+    DuplicateError, Entry, Iterable, Lookup, NoticeError, Observation, Observer, Recorder,
+};
+use core::cell::RefCell;
+use core::fmt::{self, Debug, Display};
+use core::hash::Hash;
+
+/// The error returned by [`DepthGuardedObserver::notice`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DepthError<'a, T>
+where
+    T: ?Sized,
+{
+    /// The item is already observed, i.e. the traversal has come back to
+    /// it.
+    Cycle(DuplicateError<'a, T>),
+    /// As many observations as allowed are alive, i.e. the traversal is
+    /// as deep as allowed. Holds the maximum depth.
+    TooDeep(usize),
+    /// The recorder refused the item; see [`NoticeError::Refused`].
+    Refused(&'a T),
+}
+
+impl<'a, T> Debug for DepthError<'a, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(err) => f.debug_tuple("Cycle").field(err).finish(),
+            Self::TooDeep(max) => f.debug_tuple("TooDeep").field(max).finish(),
+            Self::Refused(item) => f.debug_tuple("Refused").field(item).finish(),
+        }
+    }
+}
+
+impl<'a, T> Display for DepthError<'a, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(err) => write!(f, "cycle: {}", err),
+            Self::TooDeep(max) => write!(f, "maximum depth of {} exceeded", max),
+            Self::Refused(item) => Display::fmt(&NoticeError::Refused(*item), f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T> std::error::Error for DepthError<'a, T> where T: ?Sized + Debug {}
+
+/**
+DepthRecorder is a [`Recorder`] that wraps another, and refuses new items
+while it holds `max_depth` of them, however they were noticed.
+*/
+#[derive(Clone, Debug)]
+pub struct DepthRecorder<R> {
+    recorder: R,
+    max_depth: usize,
+}
+
+impl<R> DepthRecorder<R> {
+    /// Creates a recorder that holds up to `max_depth` items at once in
+    /// `recorder`.
+    pub fn new(max_depth: usize, recorder: R) -> Self {
+        Self {
+            recorder,
+            max_depth,
+        }
+    }
+
+    /// Returns the most items held at once.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+}
+
+impl<K, R> Recorder<K> for DepthRecorder<R>
+where
+    R: Recorder<K>,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        self.recorder.len() < self.max_depth && self.recorder.insert(item, entry)
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        self.recorder.remove(item)
+    }
+
+    fn remove_all(&mut self, item: &K) -> bool {
+        self.recorder.remove_all(item)
+    }
+
+    fn undo_insert(&mut self, item: &K) -> bool {
+        self.recorder.undo_insert(item)
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        self.recorder.contains(item)
+    }
+
+    fn can_insert(&self, item: &K) -> bool {
+        self.recorder.len() < self.max_depth && self.recorder.can_insert(item)
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        self.recorder.entry(item)
+    }
+
+    fn len(&self) -> usize {
+        self.recorder.len()
+    }
+
+    fn clear(&mut self) {
+        self.recorder.clear()
+    }
+
+    fn reset_generations(&mut self) {
+        self.recorder.reset_generations()
+    }
+}
+
+impl<K, R> Iterable<K> for DepthRecorder<R>
+where
+    R: Iterable<K>,
+{
+    type Iter<'r>
+        = R::Iter<'r>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.recorder.iter()
+    }
+}
+
+impl<K, Q, R> Lookup<K, Q> for DepthRecorder<R>
+where
+    Q: ?Sized,
+    R: Lookup<K, Q>,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        self.recorder.contains_ref(item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        self.recorder.entry_ref(item)
+    }
+}
+
+/**
+DepthGuardedObserver is an [`Observer`] that also limits how many items may
+be observed at once, which in a recursive traversal is its depth, telling a
+cycle apart from going too deep.
+```
+use forgetful::{DepthError, DepthGuardedObserver};
+let observer = DepthGuardedObserver::new(2);
+let _a = observer.notice("a").unwrap();
+assert!(matches!(observer.notice("a"), Err(DepthError::Cycle(_))));
+let _b = observer.notice("b").unwrap();
+assert!(matches!(observer.notice("c"), Err(DepthError::TooDeep(2))));
+```
+The limit is kept by its [`DepthRecorder`], so it also holds for items
+noticed through [`observer`](DepthGuardedObserver::observer), as children,
+in scopes or in batches: at the maximum depth, those are refused, as by a
+full recorder.
+*/
+pub struct DepthGuardedObserver<'a, T, R = HashMap<&'a T, Entry, DefaultHashBuilder>>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    observer: Observer<'a, T, DepthRecorder<R>>,
+}
+
+impl<'a, T, R> Debug for DepthGuardedObserver<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+    Observer<'a, T, DepthRecorder<R>>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.observer.fmt(f)
+    }
+}
+
+impl<'a, T> DepthGuardedObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Creates an observer allowing up to `max_depth` observations at once.
+    pub fn new(max_depth: usize) -> Self {
+        Self::with_recorder(max_depth, HashMap::default())
+    }
+}
+
+impl<'a, T, R> DepthGuardedObserver<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Creates an observer allowing up to `max_depth` observations at once,
+    /// storing them in `recorder`.
+    pub fn with_recorder(max_depth: usize, recorder: R) -> Self {
+        Self {
+            observer: Observer::with_recorder(DepthRecorder::new(max_depth, recorder)),
+        }
+    }
+
+    /// Observes `item`, failing with [`DepthError::Cycle`] if it is already
+    /// observed, or else with [`DepthError::TooDeep`] if as many items as
+    /// allowed are observed, or [`DepthError::Refused`] if the recorder
+    /// refuses it.
+    #[track_caller]
+    pub fn notice(
+        &self,
+        item: &'a T,
+    ) -> Result<Observation<'a, T, DepthRecorder<R>>, DepthError<'a, T>> {
+        if self.depth() >= self.max_depth() && !self.observer.observes(item) {
+            return Err(DepthError::TooDeep(self.max_depth()));
+        }
+        self.observer.notice_checked(item).map_err(|err| match err {
+            NoticeError::Duplicate(err) => DepthError::Cycle(err),
+            NoticeError::Refused(item) => DepthError::Refused(item),
+        })
+    }
+
+    /// Returns the number of items observed.
+    pub fn depth(&self) -> usize {
+        self.observer.len()
+    }
+
+    /// Returns the most items allowed to be observed at once.
+    pub fn max_depth(&self) -> usize {
+        RefCell::borrow(&self.observer.shared.recorder).max_depth()
+    }
+
+    /// Returns the underlying observer, whose notices are also limited to
+    /// the maximum depth.
+    pub fn observer(&self) -> &Observer<'a, T, DepthRecorder<R>> {
+        &self.observer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visit<'a>(
+        observer: &DepthGuardedObserver<'a, str>,
+        graph: &'a [(&'a str, &'a str)],
+        node: &'a str,
+    ) -> Result<(), DepthError<'a, str>> {
+        let _visiting = observer.notice(node)?;
+        for (_, next) in graph.iter().filter(|(from, _)| *from == node) {
+            visit(observer, graph, next)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tells_cycles_from_depth() {
+        let chain = [("a", "b"), ("b", "c"), ("c", "d")];
+        assert!(visit(&DepthGuardedObserver::new(4), &chain, "a").is_ok());
+        let err = visit(&DepthGuardedObserver::new(3), &chain, "a").unwrap_err();
+        assert_eq!(err, DepthError::TooDeep(3));
+        assert_eq!(err.to_string(), "maximum depth of 3 exceeded");
+
+        let cycle = [("a", "b"), ("b", "a")];
+        let observer = DepthGuardedObserver::new(4);
+        match visit(&observer, &cycle, "a") {
+            Err(DepthError::Cycle(err)) => assert_eq!(err.item(), "a"),
+            other => panic!("expected a cycle, got {:?}", other),
+        }
+        assert_eq!(observer.depth(), 0);
+    }
+
+    #[test]
+    fn every_way_of_noticing_is_limited() {
+        let d = DepthGuardedObserver::new(1);
+        let g = d.notice(&1).unwrap();
+        assert!(g.notice_child(&2).is_none());
+        assert!(d.observer().notice(&2).is_none());
+        assert!(d.observer().scope().notice(&2).is_none());
+        assert!(d.observer().notice_all([&2]).is_none());
+        assert_eq!(d.depth(), 1);
+        assert_eq!(d.observer().stats().duplicates(), 0);
+        drop(g);
+        assert!(d.observer().notice(&2).is_some());
+    }
+}
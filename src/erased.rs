@@ -0,0 +1,119 @@
+use crate::{Observation, Observer};
+use core::any::{Any, TypeId};
+use core::hash::{Hash, Hasher};
+
+/**
+A key of any `'static` type that is `Eq + Hash`, so keys of different types
+can be observed by one [`TypeErasedObserver`].
+
+Keys of different types are always different keys, even if they would
+otherwise compare equal. `AnyKey` is implemented for every such type, and
+`&K` coerces to `&dyn AnyKey`.
+*/
+pub trait AnyKey: Any {
+    /// Returns true if `other` is a key of the same type, equal to this one.
+    fn eq_key(&self, other: &dyn AnyKey) -> bool;
+
+    /// Feeds the key's type and value into `state`.
+    fn hash_key(&self, state: &mut dyn Hasher);
+
+    /// Returns the key as a `dyn Any`, to downcast it.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<K> AnyKey for K
+where
+    K: Any + Eq + Hash,
+{
+    fn eq_key(&self, other: &dyn AnyKey) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<K>()
+            .is_some_and(|other| self == other)
+    }
+
+    fn hash_key(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<K>().hash(&mut state);
+        self.hash(&mut state);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl dyn AnyKey {
+    /// Returns the key as a `K`, or None if it is of another type.
+    pub fn downcast_ref<K: Any>(&self) -> Option<&K> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Returns true if the key is a `K`.
+    pub fn is<K: Any>(&self) -> bool {
+        self.as_any().is::<K>()
+    }
+}
+
+impl PartialEq for dyn AnyKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_key(other)
+    }
+}
+
+impl Eq for dyn AnyKey {}
+
+impl Hash for dyn AnyKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_key(state)
+    }
+}
+
+/**
+TypeErasedObserver is an [`Observer`] of [`AnyKey`]s, so that one observer
+can notice keys of several types, as in a traversal that reaches several
+kinds of node.
+```
+use forgetful::TypeErasedObserver;
+
+#[derive(PartialEq, Eq, Hash)]
+struct FileId(u32);
+#[derive(PartialEq, Eq, Hash)]
+struct ModuleId(u32);
+
+let observer = TypeErasedObserver::new();
+let _file = observer.notice(&FileId(1)).expect("never seen before");
+assert!(observer.notice(&FileId(1)).is_none());
+// Same value, different type.
+let module = observer.notice(&ModuleId(1)).expect("never seen before");
+assert_eq!(module.item().downcast_ref::<ModuleId>().map(|id| id.0), Some(1));
+```
+*/
+pub type TypeErasedObserver<'a> = Observer<'a, dyn AnyKey>;
+
+/// The observation type of [`TypeErasedObserver`].
+pub type TypeErasedObservation<'a> = Observation<'a, dyn AnyKey>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_of_different_types_differ() {
+        let o = TypeErasedObserver::new();
+        let _a = o.notice(&1u32).unwrap();
+        let _b = o.notice(&1u64).unwrap();
+        let _c = o.notice(&"1").unwrap();
+        assert!(o.notice(&1u32).is_none());
+        assert!(o.is_observing(&1u64));
+        assert_eq!(o.len(), 3);
+    }
+
+    #[test]
+    fn downcasts_the_key() {
+        let o = TypeErasedObserver::new();
+        let g = o.notice(&'x').unwrap();
+        assert!(g.item().is::<char>());
+        assert_eq!(g.item().downcast_ref::<char>(), Some(&'x'));
+        assert_eq!(g.item().downcast_ref::<u8>(), None);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::Entry;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display};
+
+/// The error returned by [`Observer::try_notice`](crate::Observer::try_notice)
+/// when the item is already observed.
+///
+/// ```
+/// use forgetful::Observer;
+/// let observer = Observer::new();
+/// let _foo = observer.try_notice("foo").unwrap();
+/// let err = observer.try_notice("foo").unwrap_err();
+/// assert_eq!(err.item(), "foo");
+/// assert!(err.to_string().starts_with(r#""foo" is already observed"#));
+/// ```
+///
+/// With the `track-caller` feature, the message goes on to say where the
+/// item was first noticed, e.g. `"foo" is already observed, first noticed at
+/// src/walk.rs:42:21`, and with the `timestamps` feature, how long ago.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateError<'a, T>
+where
+    T: ?Sized,
+{
+    item: &'a T,
+    entry: Option<Entry>,
+}
+
+impl<'a, T> DuplicateError<'a, T>
+where
+    T: ?Sized,
+{
+    pub(crate) fn new(item: &'a T, entry: Option<Entry>) -> Self {
+        Self { item, entry }
+    }
+
+    /// Returns the item that was already observed.
+    pub fn item(&self) -> &'a T {
+        self.item
+    }
+
+    /// Returns the entry of the existing observation, if the observer's
+    /// recorder keeps entries.
+    pub fn entry(&self) -> Option<&Entry> {
+        self.entry.as_ref()
+    }
+}
+
+impl<'a, T> Debug for DuplicateError<'a, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DuplicateError")
+            .field("item", &self.item)
+            .field("entry", &self.entry)
+            .finish()
+    }
+}
+
+impl<'a, T> Display for DuplicateError<'a, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} is already observed", self.item)?;
+        #[cfg(feature = "track-caller")]
+        if let Some(location) = self.entry.and_then(|entry| entry.location()) {
+            write!(f, ", first noticed at {}", location)?;
+        }
+        #[cfg(feature = "timestamps")]
+        if let Some(created) = self.entry.and_then(|entry| entry.created()) {
+            write!(f, ", {:?} ago", created.elapsed())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T> std::error::Error for DuplicateError<'a, T> where T: ?Sized + Debug {}
+
+/// The error returned by
+/// [`Observer::notice_checked`](crate::Observer::notice_checked).
+///
+/// ```
+/// use forgetful::{NoticeError, WeightedObserver};
+/// let observer = WeightedObserver::with_budget(1);
+/// let _a = observer.notice_checked("a").unwrap();
+/// assert!(matches!(observer.notice_checked("a"), Err(NoticeError::Duplicate(_))));
+/// assert_eq!(observer.notice_checked("b").unwrap_err(), NoticeError::Refused("b"));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NoticeError<'a, T>
+where
+    T: ?Sized,
+{
+    /// The item is already observed.
+    Duplicate(DuplicateError<'a, T>),
+    /// The recorder refused the item without observing it, e.g. because a
+    /// [`WeightedRecorder`](crate::WeightedRecorder) has no room for it.
+    Refused(&'a T),
+}
+
+impl<'a, T> NoticeError<'a, T>
+where
+    T: ?Sized,
+{
+    /// Returns the item that couldn't be observed.
+    pub fn item(&self) -> &'a T {
+        match self {
+            Self::Duplicate(err) => err.item(),
+            Self::Refused(item) => item,
+        }
+    }
+}
+
+impl<'a, T> Debug for NoticeError<'a, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate(err) => f.debug_tuple("Duplicate").field(err).finish(),
+            Self::Refused(item) => f.debug_tuple("Refused").field(item).finish(),
+        }
+    }
+}
+
+impl<'a, T> Display for NoticeError<'a, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate(err) => Display::fmt(err, f),
+            Self::Refused(item) => write!(f, "{:?} was refused by the recorder", item),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T> std::error::Error for NoticeError<'a, T> where T: ?Sized + Debug {}
+
+/// The error returned by
+/// [`OrderedObserver::notice_or_cycle`](crate::OrderedObserver) when the
+/// item is already observed, i.e. a traversal has come back to it.
+///
+/// It holds the cycle: the path from the item's observation to the current
+/// one, followed by the item again. It displays as e.g. `"a" -> "b" -> "a"`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CycleError<'a, T>
+where
+    T: ?Sized,
+{
+    path: Vec<&'a T>,
+}
+
+impl<'a, T> CycleError<'a, T>
+where
+    T: ?Sized,
+{
+    /// Makes the error for noticing `item` again, given the trail from its
+    /// observation onwards.
+    #[cfg(feature = "indexmap")]
+    pub(crate) fn new(mut trail: Vec<&'a T>, item: &'a T) -> Self {
+        trail.push(item);
+        Self { path: trail }
+    }
+
+    /// Returns the item that was already observed.
+    pub fn item(&self) -> &'a T {
+        self.path[self.path.len() - 1]
+    }
+
+    /// Returns the cycle, starting and ending with the repeated item.
+    pub fn path(&self) -> &[&'a T] {
+        &self.path
+    }
+}
+
+impl<'a, T> Debug for CycleError<'a, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CycleError")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl<'a, T> Display for CycleError<'a, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.path.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" -> ")?;
+            }
+            write!(f, "{:?}", item)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T> std::error::Error for CycleError<'a, T> where T: ?Sized + Debug {}
+
+/// The error returned when waiting to observe an item, e.g. with
+/// [`SyncObserver::notice_timeout`](crate::SyncObserver::notice_timeout),
+/// takes too long.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timeout;
+
+#[cfg(feature = "std")]
+impl Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("timed out waiting for the item to be released")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Timeout {}
+
+/// The error returned by [`PathObserver::enter`](crate::PathObserver::enter)
+/// when a directory resolves to one that is already being walked, as when a
+/// symlink points back up the tree.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymlinkCycle {
+    dir: std::path::PathBuf,
+    target: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl SymlinkCycle {
+    pub(crate) fn new(dir: std::path::PathBuf, target: std::path::PathBuf) -> Self {
+        Self { dir, target }
+    }
+
+    /// Returns the directory, as it was given to `enter`.
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    /// Returns the resolved directory, which was already being walked.
+    pub fn target(&self) -> &std::path::Path {
+        &self.target
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for SymlinkCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} leads back to {}, which is already being walked",
+            self.dir.display(),
+            self.target.display()
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SymlinkCycle {}
+
+#[cfg(all(test, feature = "track-caller"))]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn reports_where_item_was_first_noticed() {
+        let o = Observer::new();
+        let line = line!() + 1;
+        let _g = o.notice(&1);
+        let err = o.try_notice(&1).unwrap_err();
+        let location = err.entry().and_then(|entry| entry.location()).unwrap();
+        assert_eq!(location.file(), file!());
+        assert_eq!(location.line(), line);
+        assert!(err
+            .to_string()
+            .contains(&format!("first noticed at {}", location)));
+    }
+
+    #[test]
+    fn blocked_items_have_no_location() {
+        let o = Observer::with_blocked([&1]);
+        let err = o.try_notice(&1).unwrap_err();
+        assert_eq!(err.entry().unwrap().location(), None);
+        assert_eq!(err.to_string(), "1 is already observed");
+    }
+}
@@ -0,0 +1,178 @@
+#[cfg(feature = "std")]
+use crate::Observer;
+use crate::{Recorder, Shared};
+#[cfg(feature = "std")]
+use alloc::{collections::VecDeque, format, string::String, vec::Vec};
+use core::cell::RefCell;
+#[cfg(feature = "std")]
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Something that happened to an [`Observer`](crate::Observer).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// An item was noticed.
+    Notice,
+    /// An item was rejected, because it was already observed.
+    Duplicate,
+    /// An item was forgotten, because its observation was dropped.
+    Forget,
+}
+
+impl<K, R: Recorder<K>> Shared<K, R> {
+    /// Reports `kind` of event about `item` to whatever is listening.
+    pub(crate) fn event(&self, kind: EventKind, item: &K) {
+        let entry = || RefCell::borrow(&self.recorder).entry(item).copied();
+        self.hooks.run(kind, item, entry);
+        #[cfg(feature = "log")]
+        self.emit(kind, item);
+        #[cfg(feature = "std")]
+        if let Some(history) = self.history.borrow_mut().as_mut() {
+            history.push(kind, item);
+        }
+    }
+}
+
+/// A record of an [`Observer`]'s event, as returned by
+/// [`Observer::events`].
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    at: Instant,
+    kind: EventKind,
+    item: String,
+}
+
+#[cfg(feature = "std")]
+impl Event {
+    /// Returns when the event happened.
+    pub fn at(&self) -> Instant {
+        self.at
+    }
+
+    /// Returns what happened.
+    pub fn kind(&self) -> EventKind {
+        self.kind
+    }
+
+    /// Returns the item it happened to, formatted with `Debug`.
+    pub fn item(&self) -> &str {
+        &self.item
+    }
+}
+
+/// The most recent events of an observer.
+#[cfg(feature = "std")]
+pub(crate) struct History<K> {
+    capacity: usize,
+    events: VecDeque<Event>,
+    describe: fn(&K) -> String,
+}
+
+#[cfg(feature = "std")]
+impl<K> History<K> {
+    fn push(&mut self, kind: EventKind, item: &K) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(Event {
+            at: Instant::now(),
+            kind,
+            item: (self.describe)(item),
+        });
+    }
+}
+
+#[cfg(feature = "std")]
+fn describe<K: Debug>(item: &K) -> String {
+    format!("{:?}", item)
+}
+
+/// With the `std` feature, an observer can keep a history of its events.
+#[cfg(feature = "std")]
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Has the observer keep a record of its latest `capacity` events
+    /// from now on, each with when it happened and the item, for
+    /// [`events`](Self::events) to return. Calling it again changes the
+    /// capacity, keeping the latest of the events recorded so far.
+    ///
+    /// ```
+    /// use forgetful::{EventKind, Observer};
+    /// let observer = Observer::new();
+    /// observer.record_events(2);
+    /// let _a = observer.notice("a");
+    /// let _again = observer.notice("a");
+    /// drop(observer.notice("b"));
+    ///
+    /// let events: Vec<_> = observer.events().iter().map(|e| (e.kind(), e.item().to_owned())).collect();
+    /// assert_eq!(events, [(EventKind::Notice, r#""b""#.to_owned()), (EventKind::Forget, r#""b""#.to_owned())]);
+    /// ```
+    pub fn record_events(&self, capacity: usize)
+    where
+        T: Debug,
+    {
+        let mut history = self.shared.history.borrow_mut();
+        let mut events = history.take().map(|h| h.events).unwrap_or_default();
+        while events.len() > capacity {
+            events.pop_front();
+        }
+        *history = Some(History {
+            capacity,
+            events,
+            describe: describe::<&'a T>,
+        });
+    }
+
+    /// Returns the events recorded since
+    /// [`record_events`](Self::record_events), oldest first.
+    pub fn events(&self) -> Vec<Event> {
+        self.shared
+            .history
+            .borrow()
+            .as_ref()
+            .map_or_else(Vec::new, |history| history.events.iter().cloned().collect())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_latest_events_up_to_capacity() {
+        let o = Observer::new();
+        let _before = o.notice(&0);
+        assert!(o.events().is_empty());
+        o.record_events(3);
+        let g = o.notice(&1).unwrap();
+        let _child = g.notice_child(&2).unwrap();
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        let kinds: Vec<_> = o
+            .events()
+            .iter()
+            .map(|e| (e.kind(), e.item().to_owned()))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (EventKind::Duplicate, "1".into()),
+                (EventKind::Forget, "1".into()),
+                (EventKind::Forget, "2".into()),
+            ]
+        );
+        let events = o.events();
+        assert!(events.windows(2).all(|w| w[0].at() <= w[1].at()));
+        o.record_events(1);
+        assert_eq!(o.events().len(), 1);
+        assert_eq!(o.events()[0].item(), "2");
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{Observer, Recorder};
+
+const NOTICED: &str = "forgetful_noticed_total";
+const DUPLICATES: &str = "forgetful_duplicates_total";
+const ACTIVE: &str = "forgetful_active_observations";
+const PEAK: &str = "forgetful_peak_active_observations";
+
+/// Describes the metrics [`Observer::export_metrics`] records to the
+/// installed `metrics` recorder, so exporters can publish help text for
+/// them. Call it once, after installing the recorder.
+pub fn describe_metrics() {
+    metrics::describe_counter!(NOTICED, "Items noticed by the observer.");
+    metrics::describe_counter!(
+        DUPLICATES,
+        "Items the observer rejected because they were already observed."
+    );
+    metrics::describe_gauge!(ACTIVE, "Observations currently alive.");
+    metrics::describe_gauge!(PEAK, "The most observations alive at once.");
+}
+
+/// With the `metrics` feature, an observer's [stats](Observer::stats) can be
+/// recorded through the `metrics` facade.
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Records the observer's [stats](Self::stats) to the installed
+    /// `metrics` recorder, labelled with `observer="<name>"`:
+    ///
+    /// - `forgetful_noticed_total` and `forgetful_duplicates_total`, counters
+    ///   of the items noticed and rejected as duplicates;
+    /// - `forgetful_active_observations` and
+    ///   `forgetful_peak_active_observations`, gauges of the observations
+    ///   alive now and at most.
+    ///
+    /// Observers aren't `Send`, so the recorder can't read them itself; call
+    /// this periodically, e.g. after each batch of work, to keep the metrics
+    /// current.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let _job = observer.notice("job");
+    /// observer.export_metrics("jobs");
+    /// ```
+    pub fn export_metrics(&self, name: &'static str) {
+        let stats = self.stats();
+        metrics::counter!(NOTICED, "observer" => name).absolute(stats.noticed());
+        metrics::counter!(DUPLICATES, "observer" => name).absolute(stats.duplicates());
+        metrics::gauge!(ACTIVE, "observer" => name).set(stats.active() as f64);
+        metrics::gauge!(PEAK, "observer" => name).set(stats.peak() as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    #[test]
+    fn records_stats_labelled_with_the_name() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let o = Observer::new();
+        let _a = o.notice(&1);
+        let _again = o.notice(&1);
+        metrics::with_local_recorder(&recorder, || o.export_metrics("test"));
+        let mut metrics: Vec<(String, DebugValue)> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let key = key.key();
+                assert!(key
+                    .labels()
+                    .any(|l| l.key() == "observer" && l.value() == "test"));
+                (key.name().to_string(), value)
+            })
+            .collect();
+        metrics.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(
+            metrics,
+            [
+                (
+                    "forgetful_active_observations".into(),
+                    DebugValue::Gauge(1.0.into())
+                ),
+                ("forgetful_duplicates_total".into(), DebugValue::Counter(1)),
+                ("forgetful_noticed_total".into(), DebugValue::Counter(1)),
+                (
+                    "forgetful_peak_active_observations".into(),
+                    DebugValue::Gauge(1.0.into())
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Observers pre-wired to fast, non-cryptographic hashers.
+//!
+//! The default hasher (SipHash) is resistant to HashDoS, which matters little
+//! for most traversals and costs a lot when items are small, e.g. integers.
+
+#[cfg(feature = "ahash")]
+pub mod ahash {
+    //! Aliases using [`ahash`](https://docs.rs/ahash), available with the
+    //! `ahash` feature.
+    //!
+    //! ```
+    //! use forgetful::ahash::FastObserver;
+    //! let observer = FastObserver::default();
+    //! let _observation = observer.notice(&42).expect("never seen before");
+    //! assert!(observer.notice(&42).is_none());
+    //! ```
+
+    /// An [`Observer`](crate::Observer) hashing with ahash.
+    pub type FastObserver<'a, T> =
+        crate::Observer<'a, T, crate::hash::HashMap<&'a T, crate::Entry, ::ahash::RandomState>>;
+
+    /// The observation type of [`FastObserver`].
+    pub type FastObservation<'a, T> =
+        crate::Observation<'a, T, crate::hash::HashMap<&'a T, crate::Entry, ::ahash::RandomState>>;
+}
+
+#[cfg(feature = "fxhash")]
+pub mod fxhash {
+    //! Aliases using FxHash (via [`rustc-hash`](https://docs.rs/rustc-hash)),
+    //! available with the `fxhash` feature.
+    //!
+    //! ```
+    //! use forgetful::fxhash::FastObserver;
+    //! let observer = FastObserver::default();
+    //! let _observation = observer.notice(&42).expect("never seen before");
+    //! assert!(observer.notice(&42).is_none());
+    //! ```
+
+    /// An [`Observer`](crate::Observer) hashing with FxHash.
+    pub type FastObserver<'a, T> = crate::Observer<
+        'a,
+        T,
+        crate::hash::HashMap<&'a T, crate::Entry, ::rustc_hash::FxBuildHasher>,
+    >;
+
+    /// The observation type of [`FastObserver`].
+    pub type FastObservation<'a, T> = crate::Observation<
+        'a,
+        T,
+        crate::hash::HashMap<&'a T, crate::Entry, ::rustc_hash::FxBuildHasher>,
+    >;
+}
@@ -0,0 +1,113 @@
+use crate::hash::{hash_set, DefaultHashBuilder, HashSet};
+use crate::{Iterable, Observer};
+use core::fmt::{self, Debug};
+use core::hash::Hash;
+use core::iter::Copied;
+
+/**
+An immutable copy of the items an [`Observer`] was observing at some point,
+made by [`Observer::freeze`].
+
+Unlike the observer, it doesn't change as items are noticed and forgotten,
+so it can be queried long after, or compared with a later copy.
+```
+use forgetful::Observer;
+let observer = Observer::new();
+let _a = observer.notice("a");
+let b = observer.notice("b");
+let before = observer.freeze();
+drop(b);
+let _c = observer.notice("c");
+let after = observer.freeze();
+assert!(before.contains("b") && !after.contains("b"));
+assert_eq!(after.difference(&before).collect::<Vec<_>>(), ["c"]);
+```
+*/
+#[derive(Clone, PartialEq, Eq)]
+pub struct FrozenSet<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    items: HashSet<&'a T, DefaultHashBuilder>,
+}
+
+impl<'a, T> Debug for FrozenSet<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.items.fmt(f)
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + Eq + Hash + ?Sized,
+    R: Iterable<&'a T>,
+{
+    /// Returns a [`FrozenSet`] of the items currently observed.
+    pub fn freeze(&self) -> FrozenSet<'a, T> {
+        FrozenSet {
+            items: self.observed().iter().collect(),
+        }
+    }
+}
+
+impl<'a, T> FrozenSet<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Returns true if `item` was observed when the set was made.
+    pub fn contains(&self, item: &'a T) -> bool {
+        self.items.contains(&item)
+    }
+
+    /// Returns the number of items in the set.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterates over the items, in no particular order.
+    pub fn iter(&self) -> Copied<hash_set::Iter<'_, &'a T>> {
+        self.items.iter().copied()
+    }
+
+    /// Iterates over the items in this set that aren't in `other`.
+    pub fn difference<'s>(&'s self, other: &'s Self) -> impl Iterator<Item = &'a T> + 's {
+        self.items.difference(&other.items).copied()
+    }
+}
+
+impl<'s, 'a, T> IntoIterator for &'s FrozenSet<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    type Item = &'a T;
+    type IntoIter = Copied<hash_set::Iter<'s, &'a T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::OrdObserver;
+
+    #[test]
+    fn is_independent_of_the_observer() {
+        let o = OrdObserver::default();
+        let g = o.notice(&1);
+        let frozen = o.freeze();
+        drop(g);
+        let _g = o.notice(&2);
+        assert!(frozen.contains(&1) && !frozen.contains(&2));
+        assert_eq!(frozen.iter().collect::<Vec<_>>(), [&1]);
+        assert_ne!(frozen, o.freeze());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{OwnedSyncObservation, OwnedSyncObserver};
+use core::borrow::Borrow;
+use core::cmp::Eq;
+use core::hash::Hash;
+use std::sync::OnceLock;
+
+/**
+GlobalObserver is an [`OwnedSyncObserver`] that can be declared as a
+`static`, so that independent parts of a program share one set of
+observations without passing a handle around.
+
+The observer is created the first time it is used.
+```
+use forgetful::GlobalObserver;
+use std::path::{Path, PathBuf};
+
+static LOADING: GlobalObserver<PathBuf> = GlobalObserver::new();
+
+fn load_config(path: &Path) -> Result<(), String> {
+    let _loading = LOADING
+        .notice(path.to_path_buf())
+        .ok_or(format!("{} is already being loaded", path.display()))?;
+    // ...
+    Ok(())
+}
+
+assert!(load_config(Path::new("app.toml")).is_ok());
+```
+*/
+pub struct GlobalObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    observer: OnceLock<OwnedSyncObserver<T>>,
+}
+
+impl<T> Default for GlobalObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> GlobalObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    pub const fn new() -> Self {
+        Self {
+            observer: OnceLock::new(),
+        }
+    }
+
+    /// Returns the underlying observer, creating it if needed.
+    pub fn get(&self) -> &OwnedSyncObserver<T> {
+        self.observer.get_or_init(OwnedSyncObserver::new)
+    }
+
+    /// Notices `item`. See [`OwnedSyncObserver::notice`].
+    pub fn notice(&self, item: T) -> Option<OwnedSyncObservation<T>> {
+        self.get().notice(item)
+    }
+
+    /// Returns true if `item` is currently observed. See
+    /// [`OwnedSyncObserver::is_observing`].
+    pub fn is_observing<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get().is_observing(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SEEN: GlobalObserver<u32> = GlobalObserver::new();
+
+    #[test]
+    fn shares_observations_between_threads() {
+        let g = SEEN.notice(1).unwrap();
+        std::thread::spawn(|| assert!(SEEN.notice(1).is_none()))
+            .join()
+            .unwrap();
+        drop(g);
+        assert!(!SEEN.is_observing(&1));
+    }
+}
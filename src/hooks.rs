@@ -0,0 +1,189 @@
+use crate::{Entry, EventKind, Observer, Recorder};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::RefCell;
+
+/// A callback, kept as `Any` with a function to call it, rather than as a
+/// `dyn Fn(&K)`, so that items needn't strictly outlive their observer.
+struct Hook<K> {
+    f: Box<dyn Any>,
+    call: fn(&dyn Any, &K, Option<Entry>),
+}
+
+impl<'a, T: 'a + ?Sized> Hook<&'a T> {
+    fn new<F: Fn(&'a T) + 'static>(f: F) -> Self {
+        Self {
+            f: Box::new(f),
+            call: |f, item, _| f.downcast_ref::<F>().expect("hooks keep their type")(item),
+        }
+    }
+
+    fn with_entry<F: Fn(&'a T, Option<Entry>) + 'static>(f: F) -> Self {
+        Self {
+            f: Box::new(f),
+            call: |f, item, entry| {
+                f.downcast_ref::<F>().expect("hooks keep their type")(item, entry)
+            },
+        }
+    }
+}
+
+/// The callbacks registered with an [`Observer`].
+pub(crate) struct Hooks<K> {
+    notice: RefCell<Vec<Hook<K>>>,
+    duplicate: RefCell<Vec<Hook<K>>>,
+    forget: RefCell<Vec<Hook<K>>>,
+}
+
+impl<K> Default for Hooks<K> {
+    fn default() -> Self {
+        Self {
+            notice: RefCell::default(),
+            duplicate: RefCell::default(),
+            forget: RefCell::default(),
+        }
+    }
+}
+
+impl<K> Hooks<K> {
+    /// Calls the callbacks registered for `kind` of event with `item`.
+    /// `entry` is only called, to get the entry of the item a duplicate is
+    /// of, if there are callbacks for duplicates.
+    pub(crate) fn run(&self, kind: EventKind, item: &K, entry: impl FnOnce() -> Option<Entry>) {
+        let hooks = match kind {
+            EventKind::Notice => &self.notice,
+            EventKind::Duplicate => &self.duplicate,
+            EventKind::Forget => &self.forget,
+        };
+        let hooks = hooks.borrow();
+        if hooks.is_empty() {
+            return;
+        }
+        let entry = match kind {
+            EventKind::Duplicate => entry(),
+            _ => None,
+        };
+        for hook in hooks.iter() {
+            (hook.call)(&*hook.f, item, entry);
+        }
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Registers `f` to be called with each item noticed from now on, once
+    /// it is observed.
+    ///
+    /// Callbacks may use the observer, but registering another callback
+    /// from within one panics.
+    pub fn on_notice(&self, f: impl Fn(&'a T) + 'static) {
+        self.shared.hooks.notice.borrow_mut().push(Hook::new(f));
+    }
+
+    /// Registers `f` to be called with each item rejected from now on
+    /// because it is already observed, along with the [`Entry`] of the
+    /// observation holding it, if the recorder keeps entries. With the
+    /// `track-caller` feature, that says where the holder was noticed.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// use std::{cell::Cell, rc::Rc};
+    ///
+    /// let conflicts = Rc::new(Cell::new(0));
+    /// let observer = Observer::new();
+    /// let count = Rc::clone(&conflicts);
+    /// observer.on_duplicate(move |_: &str, _holder| count.set(count.get() + 1));
+    ///
+    /// let _lock = observer.notice("db");
+    /// assert!(observer.notice("db").is_none());
+    /// assert_eq!(conflicts.get(), 1);
+    /// ```
+    pub fn on_duplicate(&self, f: impl Fn(&'a T, Option<Entry>) + 'static) {
+        self.shared
+            .hooks
+            .duplicate
+            .borrow_mut()
+            .push(Hook::with_entry(f));
+    }
+
+    /// Registers `f` to be called with each item forgotten from now on
+    /// because its observation was dropped, once it is no longer observed,
+    /// e.g. to invalidate what was cached about it. Items forgotten by
+    /// [`forget_all`](Self::forget_all) aren't reported.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// use std::{cell::RefCell, rc::Rc};
+    ///
+    /// let invalidated = Rc::new(RefCell::new(Vec::new()));
+    /// let observer = Observer::new();
+    /// let log = Rc::clone(&invalidated);
+    /// observer.on_forget(move |key: &str| log.borrow_mut().push(key.to_owned()));
+    ///
+    /// drop(observer.notice("user:7"));
+    /// assert_eq!(*invalidated.borrow(), ["user:7"]);
+    /// ```
+    pub fn on_forget(&self, f: impl Fn(&'a T) + 'static) {
+        self.shared.hooks.forget.borrow_mut().push(Hook::new(f));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    #[test]
+    fn calls_back_on_notice_and_forget() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let o = Observer::new();
+        let (noticed, forgot) = (Rc::clone(&calls), Rc::clone(&calls));
+        o.on_notice(move |item: &i32| noticed.borrow_mut().push(("notice", *item)));
+        o.on_forget(move |item: &i32| forgot.borrow_mut().push(("forget", *item)));
+        let g = o.notice(&1).unwrap();
+        let _child = g.notice_child(&2).unwrap();
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert_eq!(
+            *calls.borrow(),
+            [("notice", 1), ("notice", 2), ("forget", 1), ("forget", 2)]
+        );
+    }
+
+    #[test]
+    fn callbacks_may_use_the_observer() {
+        let o = Rc::new(Observer::new());
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (observer, log) = (Rc::downgrade(&o), Rc::clone(&seen));
+        o.on_forget(move |item: &i32| {
+            let observer = observer.upgrade().unwrap();
+            log.borrow_mut().push((*item, observer.is_observing(item)));
+        });
+        drop(o.notice(&1));
+        assert_eq!(*seen.borrow(), [(1, false)]);
+    }
+
+    #[test]
+    fn duplicate_callbacks_get_the_holders_entry() {
+        let entries = Rc::new(RefCell::new(Vec::new()));
+        let o = Observer::with_blocked([&0]);
+        let log = Rc::clone(&entries);
+        o.on_duplicate(move |item: &i32, entry| log.borrow_mut().push((*item, entry)));
+        let _a = o.notice(&1).unwrap();
+        assert!(o.notice(&0).is_none());
+        assert!(o.notice_all([&2, &1]).is_none());
+        let entries = entries.borrow();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], (0, Some(crate::Entry::default())));
+        assert_eq!(entries[1].0, 1);
+        #[cfg(feature = "track-caller")]
+        assert!(entries[1].1.unwrap().location().is_some());
+        assert!(!o.is_observing(&2));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::hash::{hash_map, DefaultHashBuilder, HashMap};
+use crate::{Entry, Iterable, Lookup, Observation, Observer, Recorder};
+use core::fmt::{self, Debug};
+use core::hash::BuildHasher;
+use core::iter::Map;
+
+/**
+IdentityRecorder is a [`Recorder`] that tells items apart by their address
+rather than by `Eq`, so two equal values in different places are different
+items, and the items needn't implement `Eq` or `Hash` at all.
+
+Zero-sized values may share an address, so they may be taken for one
+another.
+*/
+pub struct IdentityRecorder<'a, T, S = DefaultHashBuilder>
+where
+    T: ?Sized,
+{
+    items: HashMap<*const (), (&'a T, Entry), S>,
+}
+
+impl<'a, T, S> Default for IdentityRecorder<'a, T, S>
+where
+    T: ?Sized,
+    S: Default,
+{
+    fn default() -> Self {
+        Self {
+            items: HashMap::default(),
+        }
+    }
+}
+
+impl<'a, T, S> Debug for IdentityRecorder<'a, T, S>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.items.values().map(|(item, _)| item))
+            .finish()
+    }
+}
+
+fn address<T: ?Sized>(item: &T) -> *const () {
+    // Drop any metadata, so that e.g. a `dyn` item is the same item whatever
+    // vtable it's seen through.
+    item as *const T as *const ()
+}
+
+impl<'a, T, S> Recorder<&'a T> for IdentityRecorder<'a, T, S>
+where
+    T: ?Sized,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: &'a T, entry: Entry) -> bool {
+        match self.items.entry(address(item)) {
+            hash_map::Entry::Occupied(_) => false,
+            hash_map::Entry::Vacant(vacant) => {
+                vacant.insert((item, entry));
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, item: &&'a T) -> bool {
+        self.items.remove(&address(*item)).is_some()
+    }
+
+    fn contains(&self, item: &&'a T) -> bool {
+        self.items.contains_key(&address(*item))
+    }
+
+    fn entry(&self, item: &&'a T) -> Option<&Entry> {
+        self.items.get(&address(*item)).map(|(_, entry)| entry)
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn clear(&mut self) {
+        self.items.clear()
+    }
+
+    fn reset_generations(&mut self) {
+        for (_, entry) in self.items.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+impl<'a, T, S> Iterable<&'a T> for IdentityRecorder<'a, T, S>
+where
+    T: ?Sized,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = Map<hash_map::Values<'r, *const (), (&'a T, Entry)>, fn(&'r (&'a T, Entry)) -> &'r &'a T>
+    where
+        Self: 'r,
+        &'a T: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.items.values().map(|(item, _)| item)
+    }
+}
+
+impl<'a, T, S> Lookup<&'a T, T> for IdentityRecorder<'a, T, S>
+where
+    T: ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &T) -> bool {
+        self.items.contains_key(&address(item))
+    }
+
+    fn entry_ref(&self, item: &T) -> Option<&Entry> {
+        self.items.get(&address(item)).map(|(_, entry)| entry)
+    }
+}
+
+/**
+IdentityObserver is an [`Observer`] backed by an [`IdentityRecorder`], so it
+observes particular values rather than equal ones.
+
+This suits walks over `Rc` or `Arc` object graphs, where what matters is
+whether a node has been reached before, and hashing a node would mean
+hashing its whole subtree.
+```
+use forgetful::IdentityObserver;
+use std::rc::Rc;
+
+let shared = Rc::new(vec![1, 2, 3]);
+let alias = Rc::clone(&shared);
+let copy = vec![1, 2, 3];
+
+let observer = IdentityObserver::default();
+let _node = observer.notice(&*shared).expect("never seen before");
+assert!(observer.notice(&*alias).is_none());
+assert!(observer.notice(&copy).is_some());
+```
+*/
+pub type IdentityObserver<'a, T> = Observer<'a, T, IdentityRecorder<'a, T>>;
+
+/// The observation type of [`IdentityObserver`].
+pub type IdentityObservation<'a, T> = Observation<'a, T, IdentityRecorder<'a, T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+
+    // Deliberately neither Eq nor Hash.
+    #[derive(Debug)]
+    struct Node {
+        children: alloc::vec::Vec<Rc<Node>>,
+    }
+
+    #[test]
+    fn tells_equal_values_apart() {
+        let (a, b) = (1, 1);
+        let o = IdentityObserver::default();
+        let _a = o.notice(&a).unwrap();
+        assert!(o.notice(&a).is_none());
+        assert!(o.notice(&b).is_some());
+    }
+
+    #[test]
+    fn finds_shared_nodes() {
+        let leaf = Rc::new(Node {
+            children: alloc::vec![],
+        });
+        let root = Node {
+            children: alloc::vec![Rc::clone(&leaf), leaf],
+        };
+        let o = IdentityObserver::default();
+        let _first = o.notice(&*root.children[0]).unwrap();
+        assert!(o.notice(&*root.children[1]).is_none());
+        assert_eq!(o.snapshot().len(), 1);
+    }
+}
@@ -0,0 +1,186 @@
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::fmt::{self, Debug};
+use core::ops::Range;
+
+/// An observation produced by [`IntervalObserver::notice`].
+pub struct IntervalObservation<T>
+where
+    T: Ord + Copy,
+{
+    range: Range<T>,
+    /// None for an empty range, which isn't recorded.
+    ranges: Option<Rc<RefCell<BTreeMap<T, T>>>>,
+}
+
+impl<T> Debug for IntervalObservation<T>
+where
+    T: Ord + Copy + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.range)
+    }
+}
+
+impl<T> IntervalObservation<T>
+where
+    T: Ord + Copy,
+{
+    /// Returns the observed range.
+    pub fn range(&self) -> Range<T> {
+        self.range.clone()
+    }
+
+    /// Ends the observation now, rather than when it goes out of scope.
+    pub fn forget(self) {}
+}
+
+impl<T> Drop for IntervalObservation<T>
+where
+    T: Ord + Copy,
+{
+    fn drop(&mut self) {
+        if let Some(ranges) = &self.ranges {
+            ranges.borrow_mut().remove(&self.range.start);
+        }
+    }
+}
+
+/**
+IntervalObserver observes ranges, and refuses to notice a range that
+overlaps one it is already observing, e.g. to guard writes to byte ranges of
+a file.
+
+The observed ranges never overlap, so they are kept in a `BTreeMap` by their
+starts, and the only one a new range can overlap is the last one starting
+before it ends. Noticing takes logarithmic time.
+```
+use forgetful::IntervalObserver;
+let observer = IntervalObserver::new();
+let header = observer.notice(0..64).expect("nothing overlaps");
+assert!(observer.notice(32..128).is_none());
+let _body = observer.notice(64..1024).expect("ranges are half-open");
+drop(header);
+assert!(observer.notice(32..64).is_some());
+```
+
+Empty ranges overlap nothing, so noticing one always succeeds.
+*/
+pub struct IntervalObserver<T>
+where
+    T: Ord + Copy,
+{
+    ranges: Rc<RefCell<BTreeMap<T, T>>>,
+}
+
+impl<T> Default for IntervalObserver<T>
+where
+    T: Ord + Copy,
+{
+    fn default() -> Self {
+        Self {
+            ranges: Rc::default(),
+        }
+    }
+}
+
+impl<T> Debug for IntervalObserver<T>
+where
+    T: Ord + Copy + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.ranges.borrow().iter().map(|(&start, &end)| start..end))
+            .finish()
+    }
+}
+
+impl<T> IntervalObserver<T>
+where
+    T: Ord + Copy,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Observes `range`, unless it overlaps a range already observed.
+    pub fn notice(&self, range: Range<T>) -> Option<IntervalObservation<T>> {
+        if range.is_empty() {
+            return Some(IntervalObservation {
+                range,
+                ranges: None,
+            });
+        }
+        if self.overlapping(range.clone()).is_some() {
+            return None;
+        }
+        self.ranges.borrow_mut().insert(range.start, range.end);
+        Some(IntervalObservation {
+            range,
+            ranges: Some(Rc::clone(&self.ranges)),
+        })
+    }
+
+    /// Returns the observed range that overlaps `range`, if there is one.
+    pub fn overlapping(&self, range: Range<T>) -> Option<Range<T>> {
+        if range.is_empty() {
+            return None;
+        }
+        let ranges = self.ranges.borrow();
+        let (&start, &end) = ranges.range(..range.end).next_back()?;
+        (end > range.start).then_some(start..end)
+    }
+
+    /// Returns true if `point` lies in an observed range.
+    pub fn is_observing(&self, point: T) -> bool {
+        let ranges = self.ranges.borrow();
+        ranges
+            .range(..=point)
+            .next_back()
+            .is_some_and(|(_, &end)| end > point)
+    }
+
+    /// Returns the number of ranges currently observed, not counting empty
+    /// ones.
+    pub fn len(&self) -> usize {
+        self.ranges.borrow().len()
+    }
+
+    /// Returns true if no ranges are currently observed.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.borrow().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_overlapping_ranges() {
+        let o = IntervalObserver::new();
+        let _a = o.notice(10..20).unwrap();
+        let _b = o.notice(30..40).unwrap();
+        assert!(o.notice(15..16).is_none());
+        assert!(o.notice(0..11).is_none());
+        assert!(o.notice(19..31).is_none());
+        assert!(o.notice(0..100).is_none());
+        assert_eq!(o.overlapping(35..50), Some(30..40));
+        assert!(o.notice(20..30).is_some());
+        assert!(o.notice(0..10).is_some());
+    }
+
+    #[test]
+    fn forgets_dropped_ranges() {
+        let o = IntervalObserver::new();
+        let a = o.notice(0u64..8).unwrap();
+        assert!(o.is_observing(7));
+        assert!(!o.is_observing(8));
+        let _empty = o.notice(4..4).unwrap();
+        assert_eq!(o.len(), 1);
+        drop(a);
+        assert!(o.is_empty());
+        assert!(o.notice(4..6).is_some());
+    }
+}
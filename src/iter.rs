@@ -0,0 +1,90 @@
+use crate::{Observation, Observer, Recorder};
+
+/// Extends [`Iterator`]s with [`scoped_unique`](Self::scoped_unique).
+pub trait IteratorExt: Iterator {
+    /**
+    Filters out items that are currently observed by `observer`, and yields
+    the rest together with their observation.
+
+    An item is only skipped while an observation of it is alive, so this
+    composes with ordinary iterator pipelines in place of a manual loop
+    around [`Observer::notice`].
+    ```
+    use forgetful::{IteratorExt, Observer};
+    let observer = Observer::new();
+    let _busy = observer.notice(&2);
+    let handled: Vec<_> = [1, 2, 3, 1]
+        .iter()
+        .scoped_unique(&observer)
+        .map(|(item, _observation)| *item)
+        .collect();
+    // 2 is busy, and each 1 is forgotten once `map` is done with it.
+    assert_eq!(handled, [1, 3, 1]);
+    ```
+    */
+    fn scoped_unique<'o, 'a, T, R>(
+        self,
+        observer: &'o Observer<'a, T, R>,
+    ) -> ScopedUniqueIter<'o, 'a, Self, T, R>
+    where
+        Self: Sized + Iterator<Item = &'a T>,
+        T: 'a + ?Sized,
+        R: Recorder<&'a T>,
+    {
+        ScopedUniqueIter {
+            iter: self,
+            observer,
+        }
+    }
+}
+
+impl<I> IteratorExt for I where I: Iterator {}
+
+/// The iterator returned by [`IteratorExt::scoped_unique`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct ScopedUniqueIter<'o, 'a, I, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    iter: I,
+    observer: &'o Observer<'a, T, R>,
+}
+
+impl<'o, 'a, I, T, R> Iterator for ScopedUniqueIter<'o, 'a, I, T, R>
+where
+    I: Iterator<Item = &'a T>,
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    type Item = (&'a T, Observation<'a, T, R>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let observer = self.observer;
+        self.iter
+            .find_map(|item| observer.notice(item).map(|observation| (item, observation)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_items_while_observed() {
+        let items = [1, 2, 1, 3];
+        let o = Observer::new();
+        let held: Vec<_> = items.iter().scoped_unique(&o).collect();
+        assert_eq!(
+            held.iter().map(|(item, _)| **item).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(o.len(), 3);
+        drop(held);
+        assert!(o.is_empty());
+    }
+}
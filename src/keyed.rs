@@ -0,0 +1,288 @@
+use crate::{AsyncObservation, AsyncObserver, OwnedSyncObservation, OwnedSyncObserver};
+use core::borrow::Borrow;
+use core::cmp::Eq;
+use core::fmt::{self, Debug};
+use core::hash::Hash;
+
+/**
+KeyedLock is an async mutex per key, built on an [`AsyncObserver`].
+
+[`lock`](Self::lock) waits until no other guard for the key is alive, so
+holding an [`AsyncKeyGuard`] grants exclusive access to whatever the key
+stands for, while tasks working on other keys carry on. Keys don't need to
+be registered up front, and a released key takes no memory. Waiters on a key
+are served in FIFO order, and dropping a pending `lock` future gives up its
+place in the queue.
+```
+use forgetful::KeyedLock;
+use std::sync::Arc;
+
+# tokio::runtime::Runtime::new().unwrap().block_on(async {
+let accounts = Arc::new(KeyedLock::new());
+let alice = accounts.lock("alice").await;
+// Another account can be locked meanwhile...
+let bob = accounts.try_lock("bob").expect("bob is free");
+// ...but not the same one.
+assert!(accounts.try_lock("alice").is_none());
+drop(alice);
+assert!(accounts.try_lock("alice").is_some());
+# drop(bob);
+# });
+```
+*/
+pub struct KeyedLock<K>
+where
+    K: Eq + Hash + Clone,
+{
+    observer: AsyncObserver<K>,
+}
+
+impl<K> Default for KeyedLock<K>
+where
+    K: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Debug for KeyedLock<K>
+where
+    K: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedLock")
+            .field("locked", &self.observer)
+            .finish()
+    }
+}
+
+impl<K> KeyedLock<K>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            observer: AsyncObserver::new(),
+        }
+    }
+
+    /// Locks `key`, waiting for the current guard of it, if any, to be
+    /// dropped.
+    pub async fn lock(&self, key: K) -> AsyncKeyGuard<K> {
+        AsyncKeyGuard {
+            observation: self.observer.notice(key).await,
+        }
+    }
+
+    /// Locks `key` if it isn't already locked, without waiting.
+    pub fn try_lock(&self, key: K) -> Option<AsyncKeyGuard<K>> {
+        self.observer
+            .notice_now(key)
+            .map(|observation| AsyncKeyGuard { observation })
+    }
+
+    /// Returns true if a guard of `key` is alive. `key` may be any borrowed
+    /// form of the key type.
+    pub fn is_locked<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.observer.is_observing(key)
+    }
+}
+
+/// Exclusive access to a key of a [`KeyedLock`], until dropped.
+pub struct AsyncKeyGuard<K>
+where
+    K: Eq + Hash + Clone,
+{
+    observation: AsyncObservation<K>,
+}
+
+impl<K> AsyncKeyGuard<K>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Returns the locked key.
+    pub fn key(&self) -> &K {
+        self.observation.item()
+    }
+}
+
+impl<K> Debug for AsyncKeyGuard<K>
+where
+    K: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AsyncKeyGuard").field(self.key()).finish()
+    }
+}
+
+/**
+SyncKeyedLock is a blocking mutex per key, built on an
+[`OwnedSyncObserver`]: the threaded counterpart of [`KeyedLock`].
+
+[`lock`](Self::lock) blocks until no other [`KeyGuard`] for the key is alive,
+so at most one worker at a time handles each key.
+```
+use forgetful::SyncKeyedLock;
+use std::sync::Arc;
+use std::thread;
+
+let jobs = Arc::new(SyncKeyedLock::new());
+let workers: Vec<_> = (0..4)
+    .map(|n| {
+        let jobs = Arc::clone(&jobs);
+        thread::spawn(move || {
+            let _job = jobs.lock(n % 2);
+            // Only one worker runs job `n % 2` at a time.
+        })
+    })
+    .collect();
+for worker in workers {
+    worker.join().unwrap();
+}
+assert!(!jobs.is_locked(&0));
+```
+*/
+pub struct SyncKeyedLock<K>
+where
+    K: Eq + Hash + Clone,
+{
+    observer: OwnedSyncObserver<K>,
+}
+
+impl<K> Default for SyncKeyedLock<K>
+where
+    K: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Debug for SyncKeyedLock<K>
+where
+    K: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncKeyedLock")
+            .field("locked", &self.observer)
+            .finish()
+    }
+}
+
+impl<K> SyncKeyedLock<K>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            observer: OwnedSyncObserver::new(),
+        }
+    }
+
+    /// Locks `key`, blocking until the current guard of it, if any, is
+    /// dropped.
+    pub fn lock(&self, key: K) -> KeyGuard<K> {
+        KeyGuard {
+            observation: self.observer.notice_blocking(key),
+        }
+    }
+
+    /// Locks `key` if it isn't already locked, without blocking.
+    pub fn try_lock(&self, key: K) -> Option<KeyGuard<K>> {
+        self.observer
+            .notice(key)
+            .map(|observation| KeyGuard { observation })
+    }
+
+    /// Returns true if a guard of `key` is alive. `key` may be any borrowed
+    /// form of the key type.
+    pub fn is_locked<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.observer.is_observing(key)
+    }
+}
+
+/// Exclusive access to a key of a [`SyncKeyedLock`], until dropped.
+pub struct KeyGuard<K>
+where
+    K: Eq + Hash + Clone,
+{
+    observation: OwnedSyncObservation<K>,
+}
+
+impl<K> KeyGuard<K>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Returns the locked key.
+    pub fn key(&self) -> &K {
+        self.observation.item()
+    }
+}
+
+impl<K> Debug for KeyGuard<K>
+where
+    K: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("KeyGuard").field(self.key()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn guards_grant_exclusive_access() {
+        let lock = Arc::new(KeyedLock::new());
+        // Each key's balance is only touched under its guard, so the
+        // read-modify-write below must never interleave.
+        let balances = Arc::new(Mutex::new([0u32; 2]));
+        let tasks: Vec<_> = (0..16)
+            .map(|n| {
+                let (lock, balances) = (Arc::clone(&lock), Arc::clone(&balances));
+                tokio::spawn(async move {
+                    let guard = lock.lock(n % 2).await;
+                    let read = balances.lock().unwrap()[*guard.key()];
+                    tokio::task::yield_now().await;
+                    balances.lock().unwrap()[*guard.key()] = read + 1;
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*balances.lock().unwrap(), [8, 8]);
+        assert!(!lock.is_locked(&0));
+    }
+
+    #[test]
+    fn sync_guards_grant_exclusive_access() {
+        let lock = SyncKeyedLock::new();
+        let balances = Mutex::new([0u32; 2]);
+        std::thread::scope(|scope| {
+            for n in 0..16 {
+                let (lock, balances) = (&lock, &balances);
+                scope.spawn(move || {
+                    let guard = lock.lock(n % 2);
+                    assert!(lock.try_lock(n % 2).is_none());
+                    let read = balances.lock().unwrap()[*guard.key()];
+                    std::thread::yield_now();
+                    balances.lock().unwrap()[*guard.key()] = read + 1;
+                });
+            }
+        });
+        assert_eq!(*balances.lock().unwrap(), [8, 8]);
+        assert!(lock.try_lock(0).is_some());
+    }
+}
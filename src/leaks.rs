@@ -0,0 +1,78 @@
+use crate::{Iterable, Observer};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Iterable<&'a T>,
+{
+    /// Returns the items still observed although more than `older_than`
+    /// items have been noticed since, oldest first. A guard passed to
+    /// `mem::forget`, rather than dropped, leaves its item observed with
+    /// no other trace, so when observations are meant to be short-lived,
+    /// such items were likely leaked.
+    ///
+    /// Items observed permanently, e.g. passed to
+    /// [`with_blocked`](Self::with_blocked), aren't reported, but those
+    /// of observations [leaked](crate::Observation::leak) on purpose are.
+    /// With the `timestamps` feature, a [`Watchdog`](crate::Watchdog) can
+    /// find them by age instead.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// std::mem::forget(observer.notice("stuck"));
+    /// for step in ["a", "b", "c"] {
+    ///     let _step = observer.notice(step);
+    /// }
+    /// assert_eq!(observer.find_leaked(2), ["stuck"]);
+    /// assert!(observer.find_leaked(3).is_empty());
+    /// ```
+    pub fn find_leaked(&self, older_than: u64) -> Vec<&'a T> {
+        let current = self.shared.noticed.get();
+        let recorder = RefCell::borrow(&self.shared.recorder);
+        let mut leaked: Vec<_> = recorder
+            .iter()
+            .filter_map(|item| Some((*item, recorder.entry(item)?.generation())))
+            .filter(|&(_, generation)| {
+                generation > 0 && current.saturating_sub(generation) > older_than
+            })
+            .collect();
+        leaked.sort_by_key(|&(_, generation)| generation);
+        leaked.into_iter().map(|(item, _)| item).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn finds_items_outlived_by_newer_observations() {
+        let o = Observer::with_blocked([&0]);
+        let a = o.notice(&1).unwrap();
+        core::mem::forget(o.notice(&2).unwrap());
+        for i in &[3, 4, 5] {
+            let _g = o.notice(i).unwrap();
+        }
+        assert_eq!(o.find_leaked(2), [&1, &2]);
+        assert_eq!(o.find_leaked(3), [&1]);
+        drop(a);
+        assert_eq!(o.find_leaked(0), [&2]);
+        let err = o.try_notice(&2).unwrap_err();
+        assert_eq!(err.entry().unwrap().generation(), 2);
+    }
+
+    #[test]
+    fn copied_items_are_not_leaked() {
+        let o = Observer::new();
+        let _guards: Vec<_> = [1, 2, 3].iter().map(|i| o.notice(i).unwrap()).collect();
+        let copy = o.duplicate();
+        assert!(copy.find_leaked(0).is_empty());
+        let target = Observer::new();
+        target.merge_from(&o);
+        let _g = target.notice(&4).unwrap();
+        assert!(target.find_leaked(0).is_empty());
+    }
+}
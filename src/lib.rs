@@ -1,48 +1,529 @@
-use core::borrow::Borrow;
-use std::cell::RefCell;
-use std::cmp::Eq;
-use std::collections::HashSet;
-use std::fmt::Debug;
-use std::hash::Hash;
-use std::rc::Rc;
-
-pub struct Observation<'a, T>
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "hashbrown")))]
+compile_error!("forgetful needs either the `std` or the `hashbrown` feature enabled");
+
+use crate::hash::{DefaultHashBuilder, HashMap};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::{Cell, OnceCell, RefCell};
+use core::cmp::Eq;
+use core::fmt::Debug;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+mod array;
+mod batch;
+mod bits;
+#[cfg(feature = "std")]
+mod bloom;
+mod budget;
+mod caseless;
+mod checkpoint;
+mod compose;
+mod composite;
+#[cfg(feature = "dashmap")]
+mod concurrent;
+mod counted;
+mod depth;
+mod erased;
+mod error;
+mod events;
+#[cfg(feature = "metrics")]
+mod exporter;
+#[cfg(any(feature = "ahash", feature = "fxhash"))]
+mod fast;
+mod frozen;
+#[cfg(feature = "std")]
+mod global;
+mod hooks;
+mod identity;
+mod interval;
+mod iter;
+#[cfg(feature = "std")]
+mod keyed;
+mod leaks;
+#[cfg(feature = "std")]
+mod local;
+#[cfg(feature = "log")]
+mod logging;
+mod lru;
+mod macros;
+mod meta;
+mod normalize;
+mod observable;
+mod observed;
+mod ord;
+#[cfg(feature = "indexmap")]
+mod ordered;
+mod owned;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "std")]
+mod path;
+mod prefix;
+mod recorder;
+#[cfg(feature = "roaring")]
+mod roaring;
+mod scope;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "std")]
+mod sharded;
+mod stats;
+#[cfg(feature = "futures")]
+mod stream;
+mod strict;
+#[cfg(feature = "std")]
+mod sync;
+#[cfg(feature = "tokio")]
+mod task;
+#[cfg(feature = "timestamps")]
+mod timestamps;
+mod token;
+#[cfg(feature = "tracing")]
+mod trace;
+mod transaction;
+#[cfg(feature = "std")]
+mod ttl;
+mod view;
+#[cfg(feature = "std")]
+mod waiting;
+#[cfg(feature = "timestamps")]
+mod watchdog;
+mod weak;
+mod weighted;
+mod window;
+
+/// The hash collections used throughout the crate: those from `std` when
+/// available, otherwise those from `hashbrown`.
+mod hash {
+    #[cfg(feature = "std")]
+    pub(crate) use std::collections::{
+        hash_map, hash_map::RandomState as DefaultHashBuilder, hash_set, HashMap, HashSet,
+    };
+
+    #[cfg(not(feature = "std"))]
+    pub(crate) use hashbrown::{hash_map, hash_set, DefaultHashBuilder, HashMap, HashSet};
+}
+
+pub use array::{ArrayObservation, ArrayObserver, CapacityError};
+pub use batch::ObservationSet;
+pub use bits::{BitObservation, BitObserver, BitSet};
+#[cfg(feature = "std")]
+pub use bloom::{BloomObservation, BloomObserver, BloomRecorder};
+pub use budget::{BudgetExceeded, BudgetRecorder, BudgetedObserver};
+pub use caseless::{Ascii, CaseFold, CaselessObservation, CaselessObserver, CaselessRecorder};
+#[cfg(feature = "unicode-case")]
+pub use caseless::{Unicode, UnicodeCaselessObservation, UnicodeCaselessObserver};
+pub use checkpoint::Mark;
+pub use compose::{Intersection, Union};
+#[cfg(feature = "dashmap")]
+pub use concurrent::{ConcurrentObservation, ConcurrentObserver};
+pub use counted::{CountedObservation, CountedObserver, CountedRecorder};
+pub use depth::{DepthError, DepthGuardedObserver, DepthRecorder};
+pub use erased::{AnyKey, TypeErasedObservation, TypeErasedObserver};
+pub use error::{CycleError, DuplicateError, NoticeError};
+#[cfg(feature = "std")]
+pub use error::{SymlinkCycle, Timeout};
+#[cfg(feature = "std")]
+pub use events::Event;
+pub use events::EventKind;
+#[cfg(feature = "metrics")]
+pub use exporter::describe_metrics;
+#[cfg(feature = "ahash")]
+pub use fast::ahash;
+#[cfg(feature = "fxhash")]
+pub use fast::fxhash;
+#[cfg(feature = "macros")]
+pub use forgetful_macros::non_reentrant;
+pub use frozen::FrozenSet;
+#[cfg(feature = "std")]
+pub use global::GlobalObserver;
+pub use identity::{IdentityObservation, IdentityObserver, IdentityRecorder};
+#[cfg(feature = "indexmap")]
+pub use indexmap::Equivalent;
+pub use interval::{IntervalObservation, IntervalObserver};
+pub use iter::{IteratorExt, ScopedUniqueIter};
+#[cfg(feature = "std")]
+pub use keyed::{AsyncKeyGuard, KeyGuard, KeyedLock, SyncKeyedLock};
+#[cfg(feature = "std")]
+pub use local::LocalKeyObserver;
+pub use lru::{LruObservation, LruObserver, LruRecorder};
+pub use meta::{MetaObservation, MetaObserver};
+pub use normalize::{NormalizedObserver, Normalizer};
+pub use observable::{KeyedObservation, Observable};
+pub use observed::Observed;
+pub use ord::{OrdObservation, OrdObserver};
+#[cfg(feature = "indexmap")]
+pub use ordered::{OrderedObservation, OrderedObserver};
+pub use owned::{OwnedObservation, OwnedObserver};
+#[cfg(feature = "rayon")]
+pub use parallel::{FilterUnobserved, ParallelIteratorExt};
+#[cfg(feature = "std")]
+pub use path::{PathObserver, Resolve};
+pub use prefix::{Conflicts, PrefixObservation, PrefixObserver};
+pub use recorder::{Entry, Iterable, Lookup, Recorder};
+#[cfg(feature = "roaring")]
+pub use roaring::{RoaringObservation, RoaringObserver};
+pub use scope::Scope;
+#[cfg(feature = "std")]
+pub use sharded::ShardedObserver;
+pub use stats::Stats;
+#[cfg(feature = "futures")]
+pub use stream::{ScopedUnique, StreamExt};
+#[cfg(feature = "std")]
+pub use sync::{OwnedSyncObservation, OwnedSyncObserver, SyncObservation, SyncObserver};
+#[cfg(feature = "tokio")]
+#[doc(hidden)]
+pub use task::__tokio;
+#[cfg(feature = "tokio")]
+pub use task::TaskLocalObserver;
+pub use token::{ObservationToken, TokenObserver};
+pub use transaction::Transaction;
+pub use view::ObserverView;
+#[cfg(feature = "std")]
+pub use waiting::{AsyncObservation, AsyncObserver, Notice};
+#[cfg(feature = "timestamps")]
+pub use watchdog::Watchdog;
+pub use weak::WeakObserver;
+pub use weighted::{WeightError, WeightedObservation, WeightedObserver, WeightedRecorder};
+pub use window::{Window, WindowObservation, WindowObserver, WindowRecorder};
+
+pub struct Observation<'a, T, R = HashMap<&'a T, Entry, DefaultHashBuilder>>
 where
-    T: 'a + Eq + Hash + ?Sized,
-    &'a T: Borrow<T>,
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
 {
     item: &'a T,
-    recorder: Rc<RefCell<HashSet<&'a T>>>,
+    shared: Rc<Shared<&'a T, R>>,
+    /// The epoch the observation was made in, or None once it no longer
+    /// owns its item.
+    epoch: Option<u64>,
+    /// The observer's generation when the observation was made, so that it
+    /// won't forget a newer observation of its item; see
+    /// [`Entry::generation`].
+    generation: u64,
+    /// What it shares with its clones and children, set up by the first
+    /// [`try_clone`](Self::try_clone) or [`notice_child`](Self::notice_child).
+    link: OnceCell<Rc<Link<&'a T>>>,
+    /// The span covering the observation; see [`Observation::span`].
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
-impl<'a, T> Debug for Observation<'a, T>
+impl<'a, T, R> Debug for Observation<'a, T, R>
 where
-    T: 'a + Eq + Hash + ?Sized + Debug,
-    &'a T: Borrow<T>,
+    T: 'a + ?Sized + Debug,
+    R: Recorder<&'a T>,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        #[cfg(feature = "timestamps")]
+        if let Ok(recorder) = self.shared.recorder.try_borrow() {
+            let entry = recorder
+                .entry(&self.item)
+                .filter(|entry| self.epoch.is_some() && entry.generation() == self.generation);
+            return write!(f, "{:?}", timestamps::Timed(self.item, entry));
+        }
         write!(f, "{:?}", self.item)
     }
 }
 
-impl<'a, T> Observation<'a, T>
+impl<'a, T, R> Observation<'a, T, R>
 where
-    T: Eq + Hash + ?Sized,
-    &'a T: Borrow<T>,
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
 {
-    pub(crate) fn new(recorder: Rc<RefCell<HashSet<&'a T>>>, item: &'a T) -> Self {
-        recorder.borrow_mut().insert(item);
-        Self { item, recorder }
+    /// Returns the observed item.
+    pub fn item(&self) -> &'a T {
+        self.item
+    }
+
+    /// Ends the observation now, rather than when it goes out of scope.
+    ///
+    /// This is the same as dropping it, but states the intent.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let observation = observer.notice("foo").expect("never seen before");
+    /// observation.forget();
+    /// assert!(observer.notice("foo").is_some());
+    /// ```
+    pub fn forget(self) {}
+
+    /// Ends the observation without forgetting the item, which stays
+    /// observed permanently, as if it had been passed to
+    /// [`Observer::with_blocked`].
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// observer.notice("foo").expect("never seen before").leak();
+    /// assert!(observer.notice("foo").is_none());
+    /// ```
+    pub fn leak(mut self) {
+        if let Some(link) = self.link.get() {
+            link.owned.set(false);
+        }
+        self.epoch = None;
+        self.shared.release();
+    }
+
+    /// Returns another guard sharing this observation, so that the item is
+    /// only forgotten once the last of them is dropped. Leaking any of them
+    /// leaks the item.
+    ///
+    /// Returns None if the observation no longer owns its item, because of
+    /// a [`forget_all`](Observer::forget_all) since it was made.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let parse = observer.notice("job").unwrap();
+    /// let check = parse.try_clone().unwrap();
+    /// drop(parse);
+    /// assert!(observer.is_observing("job"));
+    /// drop(check);
+    /// assert!(!observer.is_observing("job"));
+    /// ```
+    pub fn try_clone(&self) -> Option<Self> {
+        if !self.is_armed() {
+            return None;
+        }
+        let link = self.link.get_or_init(Link::new);
+        link.holders.set(link.holders.get() + 1);
+        self.shared.acquire();
+        Some(Self {
+            item: self.item,
+            shared: Rc::clone(&self.shared),
+            epoch: self.epoch,
+            generation: self.generation,
+            link: OnceCell::from(Rc::clone(link)),
+            #[cfg(feature = "tracing")]
+            span: self.span.clone(),
+        })
+    }
+
+    /// Returns true if the observation still owns its item, i.e. it wasn't
+    /// forgotten by [`Observer::forget_all`], swept up by a parent or scope,
+    /// or expired.
+    fn is_armed(&self) -> bool {
+        let swept = self.link.get().is_some_and(|link| !link.owned.get());
+        !swept && self.epoch == Some(self.shared.epoch.get())
+    }
+
+    /// Returns true if other guards made by [`try_clone`](Self::try_clone)
+    /// still share this observation.
+    fn is_shared(&self) -> bool {
+        self.link.get().is_some_and(|link| link.holders.get() > 1)
+    }
+
+    /// Observes `item` in the same observer, as a child of this
+    /// observation: when the item of this observation is forgotten, so are
+    /// the items of its children, and theirs in turn. Returns None if `item`
+    /// is already observed.
+    ///
+    /// A child swept up this way is forgotten even if its guard is still
+    /// alive, for example because it was stashed somewhere longer-lived;
+    /// dropping that guard later does nothing.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let task = observer.notice("task").unwrap();
+    /// let step = task.notice_child("step").unwrap();
+    /// let _substep = step.notice_child("substep").unwrap();
+    /// let stashed = vec![step];
+    /// drop(task);
+    /// assert!(observer.is_empty());
+    /// # drop(stashed);
+    /// ```
+    #[track_caller]
+    pub fn notice_child(&self, item: &'a T) -> Option<Self> {
+        Self::child_of(
+            &self.shared,
+            self.link.get_or_init(Link::new),
+            Some(self),
+            item,
+        )
+    }
+
+    /// Observes `item` as a child of `parent`, to be swept up with it, and
+    /// with its span under that of `observation`, if given.
+    #[track_caller]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn child_of(
+        shared: &Rc<Shared<&'a T, R>>,
+        parent: &Link<&'a T>,
+        observation: Option<&Self>,
+        item: &'a T,
+    ) -> Option<Self> {
+        #[cfg(feature = "std")]
+        shared.expire_stale();
+        if !shared.recorder.borrow_mut().insert(item, shared.entry()) {
+            shared.reject(&item);
+            return None;
+        }
+        shared.accept(&item);
+        let epoch = shared.epoch.get();
+        let generation = shared.generation();
+        let child = Link::new();
+        let mut children = parent.children.borrow_mut();
+        children.retain(|child| child.link.owned.get());
+        children.push(Child {
+            item,
+            epoch,
+            generation,
+            link: Rc::clone(&child),
+        });
+        drop(children);
+        shared.acquire();
+        let link = OnceCell::from(child);
+        shared.log(item, generation, &link);
+        Some(Self {
+            item,
+            shared: Rc::clone(shared),
+            epoch: Some(epoch),
+            generation,
+            link,
+            #[cfg(feature = "tracing")]
+            span: shared.span(observation.map(|observation| &observation.span), &item),
+        })
+    }
+
+    /// Moves the observation to `target`, which starts observing the item
+    /// as this observer forgets it, with no window in between in which the
+    /// item is observed by neither. The original [`Entry`] goes along.
+    ///
+    /// If `target` is already observing the item, the observation is
+    /// [shared](Self::try_clone) with other guards, or it no longer owns its
+    /// item, e.g. after [`Observer::forget_all`], it is handed back
+    /// unchanged.
+    ///
+    /// Children don't go along: [`notice_child`](Self::notice_child)
+    /// observations stay in this observer, and are forgotten along with the
+    /// item, as when the observation is dropped.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let (parsing, checking) = (Observer::new(), Observer::new());
+    /// let job = parsing.notice("job").unwrap();
+    /// let job = job.transfer(&checking).unwrap();
+    /// assert!(!parsing.is_observing("job") && checking.is_observing("job"));
+    ///
+    /// let _again = parsing.notice("job").unwrap();
+    /// assert!(checking.notice("job").is_none());
+    /// # drop(job);
+    /// ```
+    #[track_caller]
+    pub fn transfer<S>(self, target: &Observer<'a, T, S>) -> Result<Observation<'a, T, S>, Self>
+    where
+        S: Recorder<&'a T>,
+    {
+        #[cfg(feature = "std")]
+        self.shared.expire_stale();
+        if !self.is_armed() {
+            return Err(self);
+        }
+        let entry = match RefCell::borrow(&self.shared.recorder).entry(&self.item) {
+            Some(entry) => *entry,
+            None => Entry::here(),
+        }
+        .in_generation(target.shared.generation() + 1);
+        if self.is_shared() || !target.shared.recorder.borrow_mut().insert(self.item, entry) {
+            return Err(self);
+        }
+        #[allow(unused_mut)]
+        let mut observation = target.observation_without_span(self.item);
+        #[cfg(feature = "tracing")]
+        {
+            observation.span = self.span.clone();
+        }
+        Ok(observation)
+    }
+}
+
+/// An observation can be used in place of the item it observes.
+///
+/// ```
+/// use forgetful::Observer;
+/// let observer = Observer::new();
+/// let observation = observer.notice("foo").expect("never seen before");
+/// assert_eq!(observation.len(), 3);
+/// ```
+impl<'a, T, R> Deref for Observation<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item
+    }
+}
+
+impl<'a, T, R> AsRef<T> for Observation<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn as_ref(&self) -> &T {
+        self.item
     }
 }
 
-impl<'a, T> Drop for Observation<'a, T>
+impl<'a, T, R> Drop for Observation<'a, T, R>
 where
-    T: Eq + Hash + ?Sized,
-    &'a T: Borrow<T>,
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
 {
     fn drop(&mut self) {
-        self.recorder.borrow_mut().remove(self.item);
+        self.end(false);
+    }
+}
+
+impl<'a, T, R> Observation<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Ends the observation as if it had never been made: like dropping it,
+    /// but the recorder [undoes](Recorder::undo_insert) the insert of its
+    /// item, and those of its children.
+    pub(crate) fn undo(mut self) {
+        self.end(true);
+    }
+
+    /// Forgets the item, unless other guards share the observation, and
+    /// the items of its children; `undo` is as for [`Shared::forget`].
+    fn end(&mut self, undo: bool) {
+        let epoch = match self.epoch.take() {
+            Some(epoch) => epoch,
+            None => return,
+        };
+        let link = self.link.get();
+        if let Some(link) = link {
+            link.holders.set(link.holders.get() - 1);
+            if link.holders.get() > 0 {
+                self.shared.release();
+                return;
+            }
+        }
+        let owned = link.map_or(true, |link| link.owned.replace(false));
+        // An observation made before the last forget_all has already been
+        // forgotten, and the item may since have been noticed again.
+        if owned && epoch == self.shared.epoch.get() {
+            self.shared.forget(&self.item, self.generation, undo);
+        }
+        if let Some(link) = link {
+            self.shared.sweep(link, undo);
+        }
+        self.shared.release();
     }
 }
 
@@ -66,51 +547,689 @@ let observer = Observer::new();
 // Now that 'observation' is out of scope, this will return Some(Observation).
 assert!(observer.notice("foo").is_some());
 ```
+
+Observations are stored in a [`Recorder`], which defaults to a `HashMap`.
+See [`Observer::with_hasher`] to change its hasher, or
+[`Observer::with_recorder`] to use a different store altogether.
 */
-pub struct Observer<'a, T>
+pub struct Observer<'a, T, R = HashMap<&'a T, Entry, DefaultHashBuilder>>
 where
-    T: 'a + Eq + Hash + ?Sized,
-    &'a T: Borrow<T>,
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
 {
-    recorder: Rc<RefCell<HashSet<&'a T>>>,
+    shared: Rc<Shared<&'a T, R>>,
+    marker: PhantomData<&'a T>,
+}
+
+/// The state an [`Observer`] shares with its observations.
+struct Shared<K, R> {
+    recorder: RefCell<R>,
+    /// Incremented by [`Observer::forget_all`], so observations can tell
+    /// whether they predate it.
+    epoch: Cell<u64>,
+    /// The number of observations alive, and the most there have been.
+    active: Cell<usize>,
+    peak: Cell<usize>,
+    /// The observations made while there are [`Mark`]s to roll back to,
+    /// oldest first, each with its sequence number.
+    log: RefCell<Vec<(u64, Child<K>)>>,
+    /// The number of marks alive, and the sequence number of the next
+    /// observation.
+    marks: Cell<usize>,
+    sequence: Cell<u64>,
+    /// The number of items noticed, and of duplicates rejected.
+    noticed: Cell<u64>,
+    duplicates: Cell<u64>,
+    /// Emits log records of the observer's events; see
+    /// [`Observer::log_events`].
+    #[cfg(feature = "log")]
+    logger: Cell<Option<logging::Logger<K>>>,
+    /// Records the item in the span of each observation; see
+    /// [`Observer::trace_items`].
+    #[cfg(feature = "tracing")]
+    describer: Cell<Option<trace::Describer<K>>>,
+    /// The callbacks registered with [`Observer::on_notice`] and the like.
+    hooks: hooks::Hooks<K>,
+    /// Set by [`Observer::strict`], to panic about duplicates.
+    strict: Cell<Option<strict::Panic<K>>>,
+    /// The observations made by [`Observer::notice_with_ttl`], each with
+    /// when it expires.
+    #[cfg(feature = "std")]
+    deadlines: RefCell<Vec<(std::time::Instant, Child<K>)>>,
+    /// The latest events; see [`Observer::record_events`].
+    #[cfg(feature = "std")]
+    history: RefCell<Option<events::History<K>>>,
+}
+
+impl<K, R> Shared<K, R> {
+    fn acquire(&self) {
+        let active = self.active.get() + 1;
+        self.active.set(active);
+        self.peak.set(self.peak.get().max(active));
+    }
+
+    fn release(&self) {
+        self.active.set(self.active.get() - 1);
+    }
+
+    /// Returns the number of items noticed.
+    fn generation(&self) -> u64 {
+        self.noticed.get()
+    }
+
+    /// Makes the entry for an item about to be noticed by the caller.
+    #[track_caller]
+    pub(crate) fn entry(&self) -> Entry {
+        Entry::here().in_generation(self.generation() + 1)
+    }
+
+    /// Counts `item` as noticed.
+    fn accept(&self, item: &K)
+    where
+        R: Recorder<K>,
+    {
+        self.noticed.set(self.noticed.get() + 1);
+        self.event(EventKind::Notice, item);
+    }
+
+    /// Counts `item` as a duplicate, rejected because it is already
+    /// observed. An item the recorder refused without holding it isn't a
+    /// duplicate, and isn't counted.
+    #[track_caller]
+    pub(crate) fn reject(&self, item: &K)
+    where
+        R: Recorder<K>,
+    {
+        if !RefCell::borrow(&self.recorder).contains(item) {
+            return;
+        }
+        self.duplicates.set(self.duplicates.get() + 1);
+        self.event(EventKind::Duplicate, item);
+        if let Some(panic) = self.strict.get() {
+            let entry = RefCell::borrow(&self.recorder).entry(item).copied();
+            panic(item, entry, core::panic::Location::caller());
+        }
+    }
+
+    /// Forgets every child of `link` that still owns its item, and their
+    /// children in turn; `undo` is as for [`forget`](Self::forget).
+    fn sweep(&self, link: &Link<K>, undo: bool)
+    where
+        R: Recorder<K>,
+    {
+        for child in link.children.take() {
+            self.sweep_child(child, undo);
+        }
+    }
+
+    /// Forgets `child` if it still owns its item, along with its children;
+    /// `undo` is as for [`forget`](Self::forget).
+    fn sweep_child(&self, child: Child<K>, undo: bool)
+    where
+        R: Recorder<K>,
+    {
+        if child.link.owned.replace(false) {
+            if child.epoch == self.epoch.get() {
+                self.forget(&child.item, child.generation, undo);
+            }
+            self.sweep(&child.link, undo);
+        }
+    }
+
+    /// Forgets `item` for an observation made in `generation`, unless it
+    /// was forgotten since and is now held by a newer observation. If
+    /// `undo`, the observation is being rolled back, and the recorder
+    /// [undoes](Recorder::undo_insert) the insert rather than removing it.
+    fn forget(&self, item: &K, generation: u64, undo: bool)
+    where
+        R: Recorder<K>,
+    {
+        let mut recorder = self.recorder.borrow_mut();
+        let newer = recorder
+            .entry(item)
+            .is_some_and(|entry| entry.generation() > generation);
+        let forgotten = !newer
+            && if undo {
+                recorder.undo_insert(item)
+            } else {
+                recorder.remove(item)
+            };
+        if forgotten {
+            drop(recorder);
+            self.event(EventKind::Forget, item);
+        }
+    }
+
+    /// Logs a new observation of `item`, if there are marks to roll it back
+    /// to.
+    fn log(&self, item: K, generation: u64, link: &OnceCell<Rc<Link<K>>>) {
+        if self.marks.get() == 0 {
+            return;
+        }
+        let sequence = self.sequence.get();
+        self.sequence.set(sequence + 1);
+        self.log.borrow_mut().push((
+            sequence,
+            Child {
+                item,
+                epoch: self.epoch.get(),
+                generation,
+                link: Rc::clone(link.get_or_init(Link::new)),
+            },
+        ));
+    }
+}
+
+/// The state shared by the clones of an [`Observation`], and with its
+/// parent or [`Scope`], if it has one.
+struct Link<K> {
+    /// The number of guards sharing the observation.
+    holders: Cell<usize>,
+    /// False once the item is no longer the observation's to forget,
+    /// because it was forgotten, swept up by a parent, or leaked.
+    owned: Cell<bool>,
+    children: RefCell<Vec<Child<K>>>,
 }
 
-impl<'a, T> Default for Observer<'a, T>
+impl<K> Link<K> {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            holders: Cell::new(1),
+            owned: Cell::new(true),
+            children: RefCell::new(Vec::new()),
+        })
+    }
+}
+
+/// An observation made with [`Observation::notice_child`] or
+/// [`Scope::notice`], as seen from its parent, or from the log of
+/// observations to roll back.
+struct Child<K> {
+    item: K,
+    epoch: u64,
+    generation: u64,
+    link: Rc<Link<K>>,
+}
+
+impl<'a, T, R> Default for Observer<'a, T, R>
 where
-    T: 'a + Eq + Hash + ?Sized,
-    &'a T: Borrow<T>,
+    T: 'a + ?Sized,
+    R: Recorder<&'a T> + Default,
 {
     fn default() -> Self {
-        Self::new()
+        Self::with_recorder(R::default())
     }
 }
 
-impl<'a, T> Debug for Observer<'a, T>
+/// Cloning an observer makes another handle to the same recorder, rather
+/// than a copy of it: an item noticed through one handle is observed by all
+/// of them. This lets several components share one observer without
+/// wrapping it in an `Rc`. See [`duplicate`](Observer::duplicate) for an
+/// independent copy.
+///
+/// ```
+/// use forgetful::Observer;
+/// let observer = Observer::new();
+/// let handle = observer.clone();
+/// let _foo = observer.notice("foo");
+/// assert!(handle.notice("foo").is_none());
+/// ```
+impl<'a, T, R> Clone for Observer<'a, T, R>
 where
-    T: 'a + Eq + Hash + ?Sized + Debug,
-    &'a T: Borrow<T>,
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        RefCell::borrow(&self.recorder).fmt(f)
+    fn clone(&self) -> Self {
+        Self {
+            shared: Rc::clone(&self.shared),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, R> Debug for Observer<'a, T, R>
+where
+    T: 'a + ?Sized + Debug,
+    R: Iterable<&'a T>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.observed().fmt(f)
     }
 }
 
 impl<'a, T> Observer<'a, T>
 where
     T: 'a + Eq + Hash + ?Sized,
-    &'a T: Borrow<T>,
 {
     pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an observer that permanently observes `items`: no observation
+    /// owns them, so they will never be forgotten and noticing them always
+    /// returns `None`.
+    ///
+    /// The same can be done with any recorder by collecting into an
+    /// Observer.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::with_blocked(["A", "B"]);
+    /// assert!(observer.notice("A").is_none());
+    /// assert!(observer.notice("C").is_some());
+    /// ```
+    pub fn with_blocked<I>(items: I) -> Self
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        items.into_iter().collect()
+    }
+}
+
+impl<'a, T, R> FromIterator<&'a T> for Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = &'a T>>(items: I) -> Self {
+        let mut recorder = R::default();
+        for item in items {
+            recorder.insert(item, Entry::default());
+        }
+        Self::with_recorder(recorder)
+    }
+}
+
+impl<'a, T, S> Observer<'a, T, HashMap<&'a T, Entry, S>>
+where
+    T: 'a + Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    /// Creates an observer which will use the given hash builder to hash
+    /// items, just like [`HashMap::with_hasher`](std::collections::HashMap::with_hasher).
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// use std::collections::hash_map::RandomState;
+    /// let observer = Observer::with_hasher(RandomState::new());
+    /// let _observation = observer.notice("foo").expect("never seen before");
+    /// assert!(observer.notice("foo").is_none());
+    /// ```
+    pub fn with_hasher(hasher: S) -> Self {
+        Self::with_recorder(HashMap::with_hasher(hasher))
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Creates an observer which stores its observations in `recorder`.
+    pub fn with_recorder(recorder: R) -> Self {
         Self {
-            recorder: Default::default(),
+            shared: Rc::new(Shared {
+                recorder: RefCell::new(recorder),
+                epoch: Cell::new(0),
+                active: Cell::new(0),
+                peak: Cell::new(0),
+                log: RefCell::new(Vec::new()),
+                marks: Cell::new(0),
+                sequence: Cell::new(0),
+                noticed: Cell::new(0),
+                duplicates: Cell::new(0),
+                #[cfg(feature = "log")]
+                logger: Cell::new(None),
+                #[cfg(feature = "tracing")]
+                describer: Cell::new(None),
+                hooks: hooks::Hooks::default(),
+                strict: Cell::new(None),
+                #[cfg(feature = "std")]
+                deadlines: RefCell::new(Vec::new()),
+                #[cfg(feature = "std")]
+                history: RefCell::new(None),
+            }),
+            marker: PhantomData,
         }
     }
 
-    pub fn notice(&self, item: &'a T) -> Option<Observation<'a, T>> {
-        if RefCell::borrow(&self.recorder).contains(item) {
-            None
+    #[track_caller]
+    pub fn notice(&self, item: &'a T) -> Option<Observation<'a, T, R>> {
+        #[cfg(feature = "std")]
+        self.shared.expire_stale();
+        if self
+            .shared
+            .recorder
+            .borrow_mut()
+            .insert(item, self.shared.entry())
+        {
+            Some(self.observation(item))
         } else {
-            Some(Observation::new(Rc::clone(&self.recorder), item))
+            self.shared.reject(&item);
+            None
+        }
+    }
+
+    /// Makes the observation of an item that has just been recorded.
+    pub(crate) fn observation(&self, item: &'a T) -> Observation<'a, T, R> {
+        #[allow(unused_mut)]
+        let mut observation = self.observation_without_span(item);
+        #[cfg(feature = "tracing")]
+        {
+            observation.span = self.shared.span(None, &item);
+        }
+        observation
+    }
+
+    /// Like [`observation`](Self::observation), but leaves the span
+    /// disabled, for the caller to fill in.
+    fn observation_without_span(&self, item: &'a T) -> Observation<'a, T, R> {
+        self.shared.accept(&item);
+        self.shared.acquire();
+        let generation = self.shared.generation();
+        let link = OnceCell::new();
+        self.shared.log(item, generation, &link);
+        Observation {
+            item,
+            shared: Rc::clone(&self.shared),
+            epoch: Some(self.shared.epoch.get()),
+            generation,
+            link,
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::none(),
+        }
+    }
+
+    /// Like [`notice`](Self::notice), but reports a duplicate as an error,
+    /// which composes with `?`.
+    ///
+    /// ```
+    /// use forgetful::{DuplicateError, Observer};
+    /// fn visit<'a>(observer: &Observer<'a, str>, node: &'a str) -> Result<(), DuplicateError<'a, str>> {
+    ///     let _visiting = observer.try_notice(node)?;
+    ///     // ...
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// With the `track-caller` feature, the error also says where the item
+    /// was first noticed, provided the recorder keeps [`Entry`]s. An item
+    /// the recorder refused is reported the same way, without an entry; use
+    /// [`notice_checked`](Self::notice_checked) to tell the two apart.
+    #[track_caller]
+    pub fn try_notice(&self, item: &'a T) -> Result<Observation<'a, T, R>, DuplicateError<'a, T>> {
+        self.notice_checked(item).map_err(|err| match err {
+            NoticeError::Duplicate(err) => err,
+            NoticeError::Refused(item) => DuplicateError::new(item, None),
+        })
+    }
+
+    /// Like [`try_notice`](Self::try_notice), but reports an item the
+    /// recorder refused, e.g. for lack of room, apart from a duplicate.
+    ///
+    /// ```
+    /// use forgetful::{NoticeError, WeightedObserver};
+    /// let observer = WeightedObserver::with_budget(1);
+    /// let _a = observer.notice_checked("a").unwrap();
+    /// assert!(matches!(observer.notice_checked("a"), Err(NoticeError::Duplicate(_))));
+    /// assert_eq!(observer.notice_checked("b").unwrap_err(), NoticeError::Refused("b"));
+    /// ```
+    #[track_caller]
+    pub fn notice_checked(&self, item: &'a T) -> Result<Observation<'a, T, R>, NoticeError<'a, T>> {
+        self.notice(item).ok_or_else(|| {
+            let recorder = RefCell::borrow(&self.shared.recorder);
+            if recorder.contains(&item) {
+                let entry = recorder.entry(&item).copied();
+                NoticeError::Duplicate(DuplicateError::new(item, entry))
+            } else {
+                NoticeError::Refused(item)
+            }
+        })
+    }
+
+    /// Runs `f` while observing `item`, returning its result, or returns
+    /// None without running it if `item` is already observed.
+    ///
+    /// The item is forgotten when `f` returns or panics. Unlike holding the
+    /// result of [`notice`](Self::notice), there is no guard that could be
+    /// dropped too early by binding it to `_`.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let nested = observer.once("foo", || observer.once("foo", || "unreachable"));
+    /// assert_eq!(nested, Some(None));
+    /// assert!(!observer.is_observing("foo"));
+    /// ```
+    #[track_caller]
+    pub fn once<F, U>(&self, item: &'a T, f: F) -> Option<U>
+    where
+        F: FnOnce() -> U,
+    {
+        let _observation = self.notice(item)?;
+        Some(f())
+    }
+
+    /// Like [`once`](Self::once), but runs `on_duplicate` instead if `item`
+    /// is already observed, so both cases are handled in one place.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// fn depth<'a>(observer: &Observer<'a, str>, node: &'a str) -> usize {
+    ///     observer.once_or(node, || 1 + depth(observer, node), || 0)
+    /// }
+    /// assert_eq!(depth(&Observer::new(), "loop"), 1);
+    /// ```
+    #[track_caller]
+    pub fn once_or<F, G, U>(&self, item: &'a T, on_first: F, on_duplicate: G) -> U
+    where
+        F: FnOnce() -> U,
+        G: FnOnce() -> U,
+    {
+        match self.notice(item) {
+            Some(_observation) => on_first(),
+            None => on_duplicate(),
+        }
+    }
+
+    /// Returns true if `item` is currently observed.
+    ///
+    /// Unlike calling [`notice`](Self::notice) and dropping the result, this
+    /// doesn't record anything, and `item` needn't borrow for as long as the
+    /// observed items: any reference will do, provided the recorder
+    /// implements [`Lookup`].
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let _a = observer.notice("a");
+    /// let query = String::from("a");
+    /// assert!(observer.is_observing(query.as_str()));
+    /// ```
+    pub fn is_observing(&self, item: &T) -> bool
+    where
+        R: Lookup<&'a T, T>,
+    {
+        #[cfg(feature = "std")]
+        self.shared.expire_stale();
+        RefCell::borrow(&self.shared.recorder).contains_ref(item)
+    }
+
+    /// Like [`is_observing`](Self::is_observing), for any recorder.
+    pub(crate) fn observes(&self, item: &'a T) -> bool {
+        #[cfg(feature = "std")]
+        self.shared.expire_stale();
+        RefCell::borrow(&self.shared.recorder).contains(&item)
+    }
+
+    /// Returns the number of items currently observed.
+    pub fn len(&self) -> usize {
+        #[cfg(feature = "std")]
+        self.shared.expire_stale();
+        RefCell::borrow(&self.shared.recorder).len()
+    }
+
+    /// Returns true if no items are currently observed.
+    pub fn is_empty(&self) -> bool {
+        #[cfg(feature = "std")]
+        self.shared.expire_stale();
+        RefCell::borrow(&self.shared.recorder).is_empty()
+    }
+
+    /// Returns the number of observations currently alive.
+    ///
+    /// Unlike [`len`](Self::len), this doesn't count items observed
+    /// permanently, but does count observations disarmed by
+    /// [`forget_all`](Self::forget_all) that haven't been dropped yet.
+    pub fn active_count(&self) -> usize {
+        self.shared.active.get()
+    }
+
+    /// Returns the most observations that have been alive at once.
+    ///
+    /// In a recursive traversal, this is the maximum depth reached.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// fn visit<'a>(observer: &Observer<'a, u32>, path: &'a [u32]) {
+    ///     if let Some((node, rest)) = path.split_first() {
+    ///         let _visiting = observer.notice(node);
+    ///         visit(observer, rest);
+    ///     }
+    /// }
+    /// let observer = Observer::new();
+    /// visit(&observer, &[0, 1, 2, 3]);
+    /// assert_eq!(observer.active_count(), 0);
+    /// assert_eq!(observer.peak_active(), 4);
+    /// ```
+    pub fn peak_active(&self) -> usize {
+        self.shared.peak.get()
+    }
+
+    /// Forgets every item, including those observed permanently.
+    ///
+    /// Outstanding observations are unaffected by this, except that dropping
+    /// them no longer does anything: in particular, it won't forget an item
+    /// that has been noticed again since.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let stale = observer.notice("foo").expect("never seen before");
+    /// observer.forget_all();
+    /// let fresh = observer.notice("foo").expect("forgotten");
+    /// drop(stale);
+    /// assert!(observer.notice("foo").is_none());
+    /// ```
+    pub fn forget_all(&self) {
+        self.shared.recorder.borrow_mut().clear();
+        self.shared.epoch.set(self.shared.epoch.get() + 1);
+        #[cfg(feature = "std")]
+        self.shared.deadlines.borrow_mut().clear();
+    }
+
+    /// Creates an independent observer that starts out observing the same
+    /// items as this one, e.g. to explore a speculative branch of a
+    /// traversal without affecting the original.
+    ///
+    /// The copied items aren't owned by any observation of the new
+    /// observer, so they stay observed there until
+    /// [`forget_all`](Self::forget_all) or [`retain`](Self::retain), just as
+    /// with [`with_blocked`](Observer::with_blocked), and are given
+    /// generation 0, see [`Recorder::reset_generations`].
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let _a = observer.notice("a");
+    /// let what_if = observer.duplicate();
+    /// let _b = what_if.notice("b");
+    /// assert!(what_if.is_observing("a"));
+    /// assert!(!observer.is_observing("b"));
+    /// ```
+    pub fn duplicate(&self) -> Self
+    where
+        R: Clone,
+    {
+        let mut recorder = RefCell::borrow(&self.shared.recorder).clone();
+        recorder.reset_generations();
+        Self::with_recorder(recorder)
+    }
+
+    /// Adopts the items `other` is currently observing, e.g. to fold the
+    /// state of a finished sub-traversal into its parent's observer. Returns
+    /// the number of items that weren't already observed here.
+    ///
+    /// The adopted items aren't owned by any observation of this observer,
+    /// so they stay observed until [`forget_all`](Self::forget_all) or
+    /// [`retain`](Self::retain), as with [`with_blocked`](Observer::with_blocked),
+    /// and have generation 0 here. The guards of `other` keep applying to
+    /// `other` alone.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let parent = Observer::new();
+    /// let _a = parent.notice("a");
+    /// let branch = Observer::new();
+    /// let b = branch.notice("b");
+    /// let _a2 = branch.notice("a");
+    /// assert_eq!(parent.merge_from(&branch), 1);
+    /// drop(b);
+    /// assert!(parent.is_observing("b"));
+    /// ```
+    pub fn merge_from<S>(&self, other: &Observer<'a, T, S>) -> usize
+    where
+        S: Iterable<&'a T>,
+    {
+        let adopted: Vec<(&'a T, Entry)> = {
+            let recorder = RefCell::borrow(&other.shared.recorder);
+            recorder
+                .iter()
+                .map(|item| {
+                    let entry = recorder.entry(item).copied().unwrap_or_default();
+                    (*item, entry.in_generation(0))
+                })
+                .collect()
+        };
+        let mut recorder = self.shared.recorder.borrow_mut();
+        adopted
+            .into_iter()
+            .filter(|(item, entry)| recorder.insert(item, *entry))
+            .count()
+    }
+
+    /// Forgets every item for which `keep` returns false. `keep` is called
+    /// for the items observed when `retain` is, and may use the observer,
+    /// e.g. to notice other items.
+    ///
+    /// Unlike [`forget_all`](Self::forget_all), this does not disarm
+    /// outstanding observations of the forgotten items. Dropping one won't
+    /// forget a newer observation of its item, provided the recorder keeps
+    /// [`Entry`]s, which tell which observation is newer; with one that
+    /// doesn't, it will forget the item again, even if it has been noticed
+    /// since.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::with_blocked(["a/1", "a/2", "b/1"]);
+    /// observer.retain(|item| !item.starts_with("a/"));
+    /// assert!(observer.notice("a/1").is_some());
+    /// assert!(observer.notice("b/1").is_none());
+    /// ```
+    pub fn retain<F>(&self, mut keep: F)
+    where
+        F: FnMut(&'a T) -> bool,
+        R: Iterable<&'a T>,
+    {
+        // Copy the items first, so that `keep` may use the observer.
+        let forgotten: Vec<&'a T> = self
+            .snapshot()
+            .into_iter()
+            .filter(|item| !keep(item))
+            .collect();
+        let mut recorder = self.shared.recorder.borrow_mut();
+        for item in forgotten {
+            recorder.remove_all(&item);
         }
     }
 }
@@ -119,6 +1238,18 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn retain_lets_the_predicate_use_the_observer() {
+        let o = Observer::with_blocked([&1, &2]);
+        let mut noticed = Vec::new();
+        o.retain(|item| {
+            noticed.extend(o.notice(&3));
+            o.is_observing(&2) && *item == 2
+        });
+        assert!(!o.is_observing(&1));
+        assert!(o.is_observing(&2) && o.is_observing(&3));
+    }
+
     #[test]
     fn forgets_immediately_with_no_observer() {
         let o = Observer::new();
@@ -142,6 +1273,239 @@ mod tests {
         assert!(g2.is_some());
     }
 
+    #[test]
+    fn uses_custom_hasher() {
+        use core::hash::{BuildHasherDefault, Hasher};
+
+        // Sends every item to the same bucket, so equality alone decides.
+        #[derive(Default)]
+        struct ConstantHasher;
+        impl Hasher for ConstantHasher {
+            fn finish(&self) -> u64 {
+                0
+            }
+            fn write(&mut self, _: &[u8]) {}
+        }
+
+        let o = Observer::with_hasher(BuildHasherDefault::<ConstantHasher>::default());
+        let g1 = o.notice(&1);
+        let g2 = o.notice(&2);
+        assert!(g1.is_some());
+        assert!(g2.is_some());
+        assert!(o.notice(&1).is_none());
+    }
+
+    #[test]
+    fn uses_custom_recorder() {
+        let o = Observer::with_recorder(alloc::collections::BTreeSet::new());
+        let g = o.notice(&1);
+        assert!(g.is_some());
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[test]
+    fn inspects_without_noticing() {
+        let o = Observer::new();
+        assert!(o.is_empty());
+        let g1 = o.notice(&1);
+        let _g2 = o.notice(&2);
+        assert!(o.is_observing(&1));
+        assert!(!o.is_observing(&3));
+        assert_eq!(o.len(), 2);
+        drop(g1);
+        assert!(!o.is_observing(&1));
+        assert_eq!(o.len(), 1);
+        assert!(!o.is_empty());
+    }
+
+    #[test]
+    fn blocked_items_are_never_forgotten() {
+        let o = Observer::with_blocked([&1, &2]);
+        assert!(o.notice(&1).is_none());
+        assert!(o.notice(&2).is_none());
+        let g = o.notice(&3);
+        assert!(g.is_some());
+        drop(g);
+        assert_eq!(o.len(), 2);
+    }
+
+    #[test]
+    fn transfer_moves_the_item_between_observers() {
+        let (a, b) = (Observer::new(), OrdObserver::default());
+        let g = a.notice(&1).unwrap();
+        let _held = b.notice(&2).unwrap();
+        let g = g.transfer(&b).unwrap();
+        assert!(a.is_empty() && b.is_observing(&1));
+        let g2 = a.notice(&2).unwrap();
+        let g2 = g2.transfer(&b).unwrap_err();
+        assert!(a.is_observing(g2.item()));
+        drop(g);
+        assert_eq!(b.len(), 1);
+        assert_eq!(a.active_count(), 1);
+    }
+
+    #[test]
+    fn disarmed_observations_are_not_transferred() {
+        let (a, b) = (Observer::new(), Observer::new());
+        let g = a.notice(&5).unwrap();
+        a.forget_all();
+        assert!(g.transfer(&b).is_err());
+        let parent = a.notice(&1).unwrap();
+        let child = parent.notice_child(&2).unwrap();
+        drop(parent);
+        assert!(child.transfer(&b).is_err());
+        assert!(b.is_empty());
+    }
+
+    #[test]
+    fn clones_share_an_observation() {
+        let o = Observer::new();
+        let g = o.notice(&1).unwrap();
+        let clones: Vec<_> = (0..3).map(|_| g.try_clone().unwrap()).collect();
+        assert_eq!(o.active_count(), 4);
+        let g = g.transfer(&Observer::new()).unwrap_err();
+        drop(g);
+        drop(clones);
+        assert!(o.is_empty());
+
+        let g = o.notice(&1).unwrap();
+        o.forget_all();
+        assert!(g.try_clone().is_none());
+    }
+
+    #[test]
+    fn dropping_a_parent_sweeps_up_its_children() {
+        let o = Observer::new();
+        let parent = o.notice(&1).unwrap();
+        let child = parent.notice_child(&2).unwrap();
+        let grandchild = child.notice_child(&3).unwrap();
+        let done = parent.notice_child(&4).unwrap();
+        assert!(parent.notice_child(&3).is_none());
+        drop(done);
+        let _again = o.notice(&4).unwrap();
+        let shared = parent.try_clone().unwrap();
+        drop(parent);
+        assert_eq!(o.len(), 4);
+        drop(shared);
+        assert!(o.is_observing(&4) && o.len() == 1);
+        let _renoticed = o.notice(&2).unwrap();
+        assert!(child.try_clone().is_none());
+        drop((child, grandchild));
+        assert!(o.is_observing(&2));
+    }
+
+    #[test]
+    fn clones_share_the_recorder() {
+        let o = Observer::new();
+        let handle = o.clone();
+        let g = handle.notice(&1).unwrap();
+        drop(handle);
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn duplicates_are_independent() {
+        let o = Observer::new();
+        let g = o.notice(&1).unwrap();
+        let copy = o.duplicate();
+        drop(g);
+        assert!(o.is_empty());
+        assert!(copy.is_observing(&1) && copy.active_count() == 0);
+        assert_eq!(copy.shared.recorder.borrow()[&&1].generation(), 0);
+        let _g = copy.notice(&2).unwrap();
+        assert!(!o.is_observing(&2));
+    }
+
+    #[test]
+    fn merge_from_adopts_items_without_guards() {
+        let parent = Observer::new();
+        let branch = OrdObserver::default();
+        let g = branch.notice(&1).unwrap();
+        assert_eq!(parent.merge_from(&branch), 1);
+        assert_eq!(parent.merge_from(&branch), 0);
+        assert_eq!(parent.merge_from(&parent.clone()), 0);
+        drop(g);
+        assert!(parent.is_observing(&1) && parent.active_count() == 0);
+
+        let branch = Observer::new();
+        let _g = branch.notice(&2).unwrap();
+        parent.merge_from(&branch);
+        assert_eq!(parent.shared.recorder.borrow()[&&2].generation(), 0);
+    }
+
+    #[test]
+    fn forget_all_disarms_outstanding_observations() {
+        let o = Observer::with_blocked([&0]);
+        let g1 = o.notice(&1);
+        let _g2 = o.notice(&2);
+        o.forget_all();
+        assert!(o.is_empty());
+        let g1_again = o.notice(&1);
+        assert!(g1_again.is_some());
+        drop(g1);
+        assert!(o.is_observing(&1));
+        assert!(o.notice(&0).is_some());
+    }
+
+    #[test]
+    fn retain_forgets_rejected_items() {
+        let o = Observer::new();
+        let guards: Vec<_> = [1, 2, 3, 4].iter().map(|i| o.notice(i)).collect();
+        o.retain(|i| i % 2 == 0);
+        assert_eq!(o.len(), 2);
+        assert!(o.notice(&1).is_some());
+        assert!(o.notice(&2).is_none());
+        drop(guards);
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn stale_observation_spares_newer_one() {
+        let o = Observer::new();
+        let stale = o.notice(&1).unwrap();
+        let child = stale.notice_child(&2).unwrap();
+        o.retain(|_| false);
+        let _fresh = o.notice(&1).unwrap();
+        let _fresh_child = o.notice(&2).unwrap();
+        drop(child);
+        drop(stale);
+        assert!(o.is_observing(&1) && o.is_observing(&2));
+
+        let counted = Observer::with_recorder(CountedRecorder::new(2));
+        let (a, b) = (counted.notice(&1).unwrap(), counted.notice(&1).unwrap());
+        drop(b);
+        drop(a);
+        assert!(counted.is_empty());
+    }
+
+    #[test]
+    fn leaked_items_are_never_forgotten() {
+        let o = Observer::new();
+        {
+            let g = o.notice(&1).unwrap();
+            g.leak();
+        }
+        assert!(o.notice(&1).is_none());
+        o.forget_all();
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[test]
+    fn observation_gives_access_to_item() {
+        fn len(s: impl AsRef<str>) -> usize {
+            s.as_ref().len()
+        }
+        let o = Observer::new();
+        let g = o.notice("foo").unwrap();
+        assert_eq!(g.item(), "foo");
+        assert!(g.starts_with("fo"));
+        assert_eq!(len(g), 3);
+    }
+
     #[test]
     fn nested_scopes() {
         let o = Observer::new();
@@ -170,4 +1534,39 @@ mod tests {
         assert!(o.notice(&2).is_some());
         assert!(o.notice(&3).is_some());
     }
+
+    #[test]
+    fn tracks_active_and_peak_observations() {
+        let o = Observer::with_blocked([&0]);
+        let g1 = o.notice(&1);
+        let g2 = o.notice(&2);
+        assert_eq!(o.active_count(), 2);
+        drop(g1);
+        let _g3 = o.notice(&3);
+        o.notice(&4).unwrap().leak();
+        assert_eq!(o.active_count(), 2);
+        o.forget_all();
+        drop(g2);
+        assert_eq!(o.active_count(), 1);
+        assert_eq!(o.peak_active(), 3);
+    }
+
+    #[test]
+    fn once_forgets_on_panic() {
+        let o = Observer::new();
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            o.once(&1, || panic!("boom"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(o.once(&1, || 2), Some(2));
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn once_or_handles_duplicates() {
+        let o = Observer::new();
+        let outer = o.once_or(&1, || o.once_or(&1, || "first", || "duplicate"), || "outer");
+        assert_eq!(outer, "duplicate");
+        assert_eq!(o.once_or(&1, || "first", || "duplicate"), "first");
+    }
 }
@@ -0,0 +1,124 @@
+use crate::{OwnedObservation, OwnedObserver};
+use core::borrow::Borrow;
+use core::cmp::Eq;
+use core::hash::Hash;
+use std::thread::LocalKey;
+
+/**
+LocalKeyObserver is a handle to a thread-local [`OwnedObserver`], declared
+with [`thread_local_observer!`](crate::thread_local_observer).
+
+It can be used from free functions, so deep recursion doesn't have to pass
+an observer along through every call. Each thread gets its own observer.
+```
+use forgetful::thread_local_observer;
+
+thread_local_observer! {
+    static LOADING: String;
+}
+
+fn load(name: &str) -> Result<(), String> {
+    let _loading = LOADING.notice(name.to_string()).ok_or(format!("{} includes itself", name))?;
+    if name == "a" {
+        load("a")?;
+    }
+    Ok(())
+}
+
+assert_eq!(load("b"), Ok(()));
+assert_eq!(load("a"), Err("a includes itself".to_string()));
+```
+*/
+pub struct LocalKeyObserver<T>
+where
+    T: 'static + Eq + Hash + Clone,
+{
+    key: &'static LocalKey<OwnedObserver<T>>,
+}
+
+impl<T> LocalKeyObserver<T>
+where
+    T: 'static + Eq + Hash + Clone,
+{
+    #[doc(hidden)]
+    pub const fn new(key: &'static LocalKey<OwnedObserver<T>>) -> Self {
+        Self { key }
+    }
+
+    /// Notices `item` in this thread's observer. See
+    /// [`OwnedObserver::notice`].
+    pub fn notice(&'static self, item: T) -> Option<OwnedObservation<T>> {
+        self.key.with(|observer| observer.notice(item))
+    }
+
+    /// Returns true if `item` is currently observed on this thread. `item`
+    /// may be any borrowed form of the item type.
+    pub fn is_observing<Q>(&'static self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.key.with(|observer| observer.is_observing(item))
+    }
+
+    /// Returns the number of items currently observed on this thread.
+    pub fn len(&'static self) -> usize {
+        self.key.with(OwnedObserver::len)
+    }
+
+    /// Returns true if no items are currently observed on this thread.
+    pub fn is_empty(&'static self) -> bool {
+        self.key.with(OwnedObserver::is_empty)
+    }
+
+    /// Runs `f` with this thread's observer.
+    pub fn with<F, U>(&'static self, f: F) -> U
+    where
+        F: FnOnce(&OwnedObserver<T>) -> U,
+    {
+        self.key.with(f)
+    }
+}
+
+/**
+Declares a static [`LocalKeyObserver`] of the given item type, backed by a
+separate [`OwnedObserver`] on each thread.
+```
+forgetful::thread_local_observer! {
+    /// Paths being visited on this thread.
+    pub static VISITING: std::path::PathBuf;
+}
+```
+*/
+#[macro_export]
+macro_rules! thread_local_observer {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::LocalKeyObserver<$t> = {
+            ::std::thread_local! {
+                static OBSERVER: $crate::OwnedObserver<$t> = $crate::OwnedObserver::new();
+            }
+            $crate::LocalKeyObserver::new(&OBSERVER)
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    thread_local_observer! {
+        static SEEN: u32;
+    }
+
+    #[test]
+    fn observes_per_thread() {
+        let _g = SEEN.notice(1).unwrap();
+        assert!(SEEN.notice(1).is_none());
+        std::thread::spawn(|| {
+            assert!(SEEN.is_empty());
+            assert!(SEEN.notice(1).is_some());
+        })
+        .join()
+        .unwrap();
+        assert_eq!(SEEN.len(), 1);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{EventKind, Observer, Recorder, Shared};
+use core::fmt::Debug;
+
+/// Emits the log record of an event.
+pub(crate) type Logger<K> = fn(EventKind, &K);
+
+fn log_event<K: Debug>(event: EventKind, item: &K) {
+    match event {
+        EventKind::Notice => log::debug!("noticed {:?}", item),
+        EventKind::Duplicate => log::debug!("already observing {:?}", item),
+        EventKind::Forget => log::debug!("forgot {:?}", item),
+    }
+}
+
+impl<K, R> Shared<K, R> {
+    /// Logs `event`, if logging is enabled.
+    pub(crate) fn emit(&self, event: EventKind, item: &K) {
+        if let Some(logger) = self.logger.get() {
+            logger(event, item)
+        }
+    }
+}
+
+/// With the `log` feature, an observer can log what it observes.
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized + Debug,
+    R: Recorder<&'a T>,
+{
+    /// Has the observer emit a debug-level log record, with the item, each
+    /// time it notices an item, is asked to notice one it is already
+    /// observing, and forgets one because its observation was dropped.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// observer.log_events();
+    /// let _job = observer.notice("job"); // logs `noticed "job"`
+    /// ```
+    pub fn log_events(&self) {
+        self.shared.logger.set(Some(log_event::<&'a T>));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use std::sync::Mutex;
+
+    /// Keeps the messages of the debug records logged.
+    struct Records(Mutex<Vec<String>>);
+
+    impl Log for Records {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.level() == Level::Debug
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static RECORDS: Records = Records(Mutex::new(Vec::new()));
+
+    #[test]
+    fn logs_notices_duplicates_and_forgets() {
+        log::set_logger(&RECORDS).unwrap();
+        log::set_max_level(LevelFilter::Debug);
+        let quiet = Observer::new();
+        let _ = quiet.notice("quiet");
+        let o = Observer::new();
+        o.log_events();
+        let job = o.notice("job").unwrap();
+        assert!(o.notice("job").is_none());
+        drop(job);
+        assert_eq!(
+            *RECORDS.0.lock().unwrap(),
+            [
+                r#"noticed "job""#,
+                r#"already observing "job""#,
+                r#"forgot "job""#
+            ]
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::hash::{hash_map, DefaultHashBuilder, HashMap};
+use crate::{Entry, Iterable, Lookup, Observation, Observer, Recorder};
+use alloc::borrow::Borrow;
+use alloc::collections::BTreeMap;
+use core::cmp::Eq;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash};
+
+/// What an [`LruRecorder`] knows about an item. It is public only so that it
+/// can appear in the recorder's iterator type.
+#[derive(Clone, Copy, Debug)]
+pub struct Slot {
+    entry: Entry,
+    /// When the item was last noticed, counting inserts.
+    noticed: u64,
+    /// False once the item has been removed, and may be evicted.
+    held: bool,
+}
+
+/**
+LruRecorder is a [`Recorder`] that keeps remembering items after they are
+removed, up to a capacity, evicting the least recently noticed of them to
+make room, so that memory stays bounded while duplicates are still caught
+as long as possible.
+
+Items still held, i.e. recorded and not yet removed, are never evicted: if
+they alone fill the recorder, it grows past its capacity until some are
+removed. Trying to insert a remembered item fails, but counts as noticing
+it, so it is kept longer.
+*/
+pub struct LruRecorder<K, S = DefaultHashBuilder> {
+    capacity: usize,
+    inserts: u64,
+    items: HashMap<K, Slot, S>,
+    /// The removed items, keyed by when each was last noticed.
+    removed: BTreeMap<u64, K>,
+}
+
+impl<K> LruRecorder<K> {
+    /// Creates a recorder remembering up to `capacity` items.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, Default::default())
+    }
+}
+
+impl<K, S> LruRecorder<K, S> {
+    /// Like [`LruRecorder::new`], but using `hasher` to hash items.
+    pub fn with_hasher(capacity: usize, hasher: S) -> Self {
+        Self {
+            capacity,
+            inserts: 0,
+            items: HashMap::with_hasher(hasher),
+            removed: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of items the recorder remembers at most, unless
+    /// more than that are held.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<K, S> LruRecorder<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns true if `item` is held, rather than only remembered.
+    pub fn is_held(&self, item: &K) -> bool {
+        self.items.get(item).is_some_and(|slot| slot.held)
+    }
+
+    /// Evicts the least recently noticed removed items until there is room
+    /// for `room` more items, or only held items are left.
+    fn evict(&mut self, room: usize) {
+        while self.items.len() + room > self.capacity {
+            match self.removed.pop_first() {
+                Some((_, item)) => {
+                    self.items.remove(&item);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl<K, S> Debug for LruRecorder<K, S>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.items.keys()).finish()
+    }
+}
+
+impl<K, S> Recorder<K> for LruRecorder<K, S>
+where
+    K: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        self.inserts += 1;
+        let noticed = self.inserts;
+        if let Some(slot) = self.items.get_mut(&item) {
+            if !slot.held {
+                let item = self
+                    .removed
+                    .remove(&slot.noticed)
+                    .expect("removed items are queued");
+                self.removed.insert(noticed, item);
+            }
+            slot.noticed = noticed;
+            return false;
+        }
+        self.evict(1);
+        self.items.insert(
+            item,
+            Slot {
+                entry,
+                noticed,
+                held: true,
+            },
+        );
+        true
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        match self.items.get_mut(item) {
+            Some(slot) if slot.held => {
+                slot.held = false;
+                self.removed.insert(slot.noticed, item.clone());
+            }
+            _ => return false,
+        }
+        self.evict(0);
+        true
+    }
+
+    fn undo_insert(&mut self, item: &K) -> bool {
+        match self.items.get(item) {
+            Some(slot) if slot.held => self.items.remove(item).is_some(),
+            _ => false,
+        }
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        self.items.contains_key(item)
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        self.items.get(item).map(|slot| &slot.entry)
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.removed.clear();
+    }
+
+    fn reset_generations(&mut self) {
+        for Slot { entry, .. } in self.items.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+impl<K, S> Iterable<K> for LruRecorder<K, S>
+where
+    K: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = hash_map::Keys<'r, K, Slot>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.items.keys()
+    }
+}
+
+impl<K, Q, S> Lookup<K, Q> for LruRecorder<K, S>
+where
+    K: Eq + Hash + Clone + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        self.items.contains_key(item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        self.items.get(item).map(|slot| &slot.entry)
+    }
+}
+
+/**
+LruObserver is an [`Observer`] that keeps remembering a bounded number of
+items after their observations are dropped, forgetting the least recently
+noticed first, as suits a long-running crawl.
+```
+use forgetful::LruObserver;
+let observer = LruObserver::bounded(2);
+drop(observer.notice("/a"));
+drop(observer.notice("/b"));
+assert!(observer.notice("/a").is_none()); // still remembered, and now more recent than /b
+drop(observer.notice("/c")); // evicts /b
+assert!(observer.notice("/b").is_some());
+```
+*/
+pub type LruObserver<'a, T> = Observer<'a, T, LruRecorder<&'a T>>;
+
+/// The observation type of [`LruObserver`].
+pub type LruObservation<'a, T> = Observation<'a, T, LruRecorder<&'a T>>;
+
+impl<'a, T> Observer<'a, T, LruRecorder<&'a T>>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Creates an observer backed by [`LruRecorder::new`].
+    pub fn bounded(capacity: usize) -> Self {
+        Self::with_recorder(LruRecorder::new(capacity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn evicts_least_recently_noticed_removed_items() {
+        let o = LruObserver::bounded(3);
+        for i in &[1, 2, 3] {
+            drop(o.notice(i).unwrap());
+        }
+        assert!(o.notice(&1).is_none());
+        drop(o.notice(&4).unwrap());
+        assert_eq!(o.len(), 3);
+        assert!(!o.is_observing(&2));
+        assert!(o.is_observing(&1) && o.is_observing(&3));
+    }
+
+    #[test]
+    fn never_evicts_held_items() {
+        let o = LruObserver::bounded(2);
+        let held: Vec<_> = [1, 2, 3].iter().map(|i| o.notice(i).unwrap()).collect();
+        assert_eq!(o.len(), 3);
+        drop(o.notice(&4).unwrap());
+        assert!(!o.is_observing(&4));
+        drop(held);
+        assert_eq!(o.len(), 2);
+        assert!(o.is_observing(&2) && o.is_observing(&3));
+    }
+
+    #[test]
+    fn rolled_back_items_are_not_remembered() {
+        let o = LruObserver::bounded(100);
+        let _two = o.notice(&2).unwrap();
+        assert!(o.notice_all([&1, &2]).is_none());
+        assert!(!o.is_observing(&1));
+        let mut t = o.begin();
+        assert!(t.notice(&3));
+        drop(t);
+        assert_eq!(o.len(), 1);
+        assert!(o.notice(&1).is_some());
+    }
+}
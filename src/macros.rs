@@ -0,0 +1,62 @@
+/**
+Runs a block while observing an item, keeping the observation alive for
+exactly the duration of the block.
+
+`observe!(observer, item => { ... })` runs the block only if `item` wasn't
+already observed. An `else` block handles the duplicate case, in which case
+the macro evaluates to the result of whichever block ran.
+
+It works with any observer whose `notice` returns an `Option`.
+```
+use forgetful::{observe, Observer};
+let observer = Observer::new();
+let found = observe!(observer, "foo" => {
+    // "foo" is observed here...
+    observe!(observer, "foo" => { "first" } else { "duplicate" })
+} else {
+    "outer duplicate"
+});
+// ...and forgotten here.
+assert_eq!(found, "duplicate");
+assert!(!observer.is_observing("foo"));
+```
+*/
+#[macro_export]
+macro_rules! observe {
+    ($observer:expr, $item:expr => $body:block else $duplicate:block) => {
+        match $observer.notice($item) {
+            ::core::option::Option::Some(_observation) => $body,
+            ::core::option::Option::None => $duplicate,
+        }
+    };
+    ($observer:expr, $item:expr => $body:block) => {
+        if let ::core::option::Option::Some(_observation) = $observer.notice($item) {
+            $body
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Observer, OwnedObserver};
+
+    #[test]
+    fn keeps_item_observed_for_the_block() {
+        let o = Observer::new();
+        let mut ran = false;
+        observe!(o, &1 => {
+            assert!(o.is_observing(&1));
+            observe!(o, &1 => { unreachable!() });
+            ran = true;
+        });
+        assert!(ran);
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn works_with_owned_observers() {
+        let o = OwnedObserver::new();
+        let n = observe!(o, 1 => { observe!(o, 1 => { 1 } else { 2 }) } else { 3 });
+        assert_eq!(n, 2);
+    }
+}
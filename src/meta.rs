@@ -0,0 +1,252 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::{Ref, RefCell, RefMut};
+use core::fmt::{self, Debug};
+use core::hash::Hash;
+
+type Items<'a, T, M> = Rc<RefCell<HashMap<&'a T, M, DefaultHashBuilder>>>;
+
+/// A callback for duplicates, kept as `Any` like the hooks of an
+/// [`Observer`](crate::Observer), so that items needn't strictly outlive
+/// their observer.
+struct DuplicateHook<K, M> {
+    f: Box<dyn Any>,
+    call: fn(&dyn Any, &K, &M),
+}
+
+impl<'a, T: 'a + ?Sized, M> DuplicateHook<&'a T, M> {
+    fn new<F: Fn(&'a T, &M) + 'static>(f: F) -> Self {
+        Self {
+            f: Box::new(f),
+            call: |f, item, meta| f.downcast_ref::<F>().expect("hooks keep their type")(item, meta),
+        }
+    }
+}
+
+/// An observation produced by [`MetaObserver::notice_with`].
+pub struct MetaObservation<'a, T, M>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    item: &'a T,
+    items: Items<'a, T, M>,
+}
+
+impl<'a, T, M> Debug for MetaObservation<'a, T, M>
+where
+    T: 'a + Eq + Hash + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.item)
+    }
+}
+
+impl<'a, T, M> MetaObservation<'a, T, M>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Returns the observed item.
+    pub fn item(&self) -> &'a T {
+        self.item
+    }
+
+    /// Returns the metadata the item was noticed with.
+    pub fn meta(&self) -> Ref<'_, M> {
+        Ref::map(self.items.borrow(), |items| &items[self.item])
+    }
+
+    /// Returns the metadata for updating, e.g. to report progress, which
+    /// others can then see through [`MetaObserver::get_meta`].
+    ///
+    /// ```
+    /// use forgetful::MetaObserver;
+    /// let observer = MetaObserver::new();
+    /// let mut job = observer.notice_with("import", 0).unwrap();
+    /// *job.meta_mut() = 50;
+    /// assert_eq!(*observer.get_meta("import").unwrap(), 50);
+    /// ```
+    pub fn meta_mut(&mut self) -> RefMut<'_, M> {
+        RefMut::map(self.items.borrow_mut(), |items| {
+            items
+                .get_mut(self.item)
+                .expect("observed items have metadata")
+        })
+    }
+
+    /// Ends the observation now, rather than when it goes out of scope.
+    pub fn forget(self) {}
+}
+
+impl<'a, T, M> Drop for MetaObservation<'a, T, M>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    fn drop(&mut self) {
+        self.items.borrow_mut().remove(self.item);
+    }
+}
+
+/**
+MetaObserver is an [`Observer`](crate::Observer) that keeps a value of
+metadata with each observation, such as who made it and why, which can be
+looked up while the observation lasts.
+```
+use forgetful::MetaObserver;
+let observer = MetaObserver::new();
+let _lock = observer.notice_with("db", "migration 42").expect("never seen before");
+assert!(observer.notice_with("db", "backup").is_none());
+assert_eq!(*observer.get_meta("db").unwrap(), "migration 42");
+```
+
+The metadata is kept in a `RefCell`, so don't hold on to what
+[`get_meta`](Self::get_meta) returns while noticing items or dropping
+observations; that panics.
+*/
+pub struct MetaObserver<'a, T, M>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    items: Items<'a, T, M>,
+    on_duplicate: RefCell<Vec<DuplicateHook<&'a T, M>>>,
+}
+
+impl<'a, T, M> Default for MetaObserver<'a, T, M>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    fn default() -> Self {
+        Self {
+            items: Rc::default(),
+            on_duplicate: RefCell::default(),
+        }
+    }
+}
+
+impl<'a, T, M> Debug for MetaObserver<'a, T, M>
+where
+    T: 'a + Eq + Hash + ?Sized + Debug,
+    M: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.items.borrow().fmt(f)
+    }
+}
+
+impl<'a, T, M> MetaObserver<'a, T, M>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Observes `item`, keeping `meta` with it until the observation is
+    /// dropped.
+    pub fn notice_with(&self, item: &'a T, meta: M) -> Option<MetaObservation<'a, T, M>> {
+        let mut items = self.items.borrow_mut();
+        if items.contains_key(item) {
+            drop(items);
+            let items = self.items.borrow();
+            for hook in self.on_duplicate.borrow().iter() {
+                (hook.call)(&*hook.f, &item, &items[item]);
+            }
+            return None;
+        }
+        items.insert(item, meta);
+        Some(MetaObservation {
+            item,
+            items: Rc::clone(&self.items),
+        })
+    }
+
+    /// Registers `f` to be called with each item rejected from now on
+    /// because it is already observed, along with the metadata of the
+    /// observation holding it, e.g. to report who holds a lock.
+    ///
+    /// Callbacks may look up metadata, but noticing items or dropping
+    /// observations from within one panics, as does registering another
+    /// callback.
+    ///
+    /// ```
+    /// use forgetful::MetaObserver;
+    /// use std::{cell::RefCell, rc::Rc};
+    ///
+    /// let conflicts = Rc::new(RefCell::new(Vec::new()));
+    /// let observer = MetaObserver::new();
+    /// let log = Rc::clone(&conflicts);
+    /// observer.on_duplicate(move |item: &str, holder: &&str| {
+    ///     log.borrow_mut().push(format!("{item} is held by {holder}"))
+    /// });
+    ///
+    /// let _lock = observer.notice_with("db", "migration 42");
+    /// assert!(observer.notice_with("db", "backup").is_none());
+    /// assert_eq!(*conflicts.borrow(), ["db is held by migration 42"]);
+    /// ```
+    pub fn on_duplicate(&self, f: impl Fn(&'a T, &M) + 'static) {
+        self.on_duplicate.borrow_mut().push(DuplicateHook::new(f));
+    }
+
+    /// Returns the metadata of the observation of `item`, or None if it
+    /// isn't observed.
+    pub fn get_meta(&self, item: &T) -> Option<Ref<'_, M>> {
+        Ref::filter_map(self.items.borrow(), |items| items.get(item)).ok()
+    }
+
+    /// Returns true if `item` is currently observed.
+    pub fn is_observing(&self, item: &T) -> bool {
+        self.items.borrow().contains_key(item)
+    }
+
+    /// Returns the number of items currently observed.
+    pub fn len(&self) -> usize {
+        self.items.borrow().len()
+    }
+
+    /// Returns true if no items are currently observed.
+    pub fn is_empty(&self) -> bool {
+        self.items.borrow().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_meta_while_observed() {
+        let o = MetaObserver::new();
+        let g = o.notice_with(&1, "first").unwrap();
+        assert!(o.notice_with(&1, "second").is_none());
+        assert_eq!(*g.meta(), "first");
+        assert_eq!(o.get_meta(&1).as_deref(), Some(&"first"));
+        drop(g);
+        assert!(o.get_meta(&1).is_none());
+        assert!(o.notice_with(&1, "second").is_some());
+    }
+
+    #[test]
+    fn holder_updates_meta() {
+        let o = MetaObserver::new();
+        let mut g = o
+            .notice_with("job", alloc::string::String::from("queued"))
+            .unwrap();
+        g.meta_mut().replace_range(.., "running");
+        assert_eq!(*o.get_meta("job").unwrap(), "running");
+        assert_eq!(*g.meta(), "running");
+    }
+
+    #[test]
+    fn duplicate_callbacks_get_the_holders_meta() {
+        let conflicts = Rc::new(RefCell::new(Vec::new()));
+        let o = MetaObserver::new();
+        let log = Rc::clone(&conflicts);
+        o.on_duplicate(move |item: &i32, meta: &&str| log.borrow_mut().push((*item, *meta)));
+        let _a = o.notice_with(&1, "first").unwrap();
+        assert!(o.notice_with(&1, "second").is_none());
+        assert!(o.notice_with(&2, "third").is_some());
+        assert_eq!(*conflicts.borrow(), [(1, "first")]);
+    }
+}
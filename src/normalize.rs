@@ -0,0 +1,151 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, OwnedObservation, OwnedObserver, Recorder};
+use core::fmt::{self, Debug};
+use core::hash::Hash;
+
+/// Turns the items a [`NormalizedObserver`] is given into the canonical
+/// items it records, so that items which only differ in form (say, in case,
+/// or in surrounding whitespace) are observed as the same item.
+///
+/// It is implemented for any `Fn(&Q) -> T`.
+pub trait Normalizer<Q: ?Sized, T> {
+    /// Returns the canonical form of `item`.
+    fn normalize(&self, item: &Q) -> T;
+}
+
+impl<Q, T, F> Normalizer<Q, T> for F
+where
+    Q: ?Sized,
+    F: Fn(&Q) -> T,
+{
+    fn normalize(&self, item: &Q) -> T {
+        self(item)
+    }
+}
+
+/**
+NormalizedObserver is an [`OwnedObserver`] which passes every item through a
+[`Normalizer`] before noticing or looking it up, so call sites can't forget
+to normalize.
+```
+use forgetful::NormalizedObserver;
+let observer = NormalizedObserver::new(|name: &str| name.trim().to_lowercase());
+let _config = observer.notice("Config").expect("never seen before");
+assert!(observer.notice(" config ").is_none());
+assert!(observer.is_observing("CONFIG"));
+```
+*/
+pub struct NormalizedObserver<T, N, R = HashMap<T, Entry, DefaultHashBuilder>>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    observer: OwnedObserver<T, R>,
+    normalizer: N,
+}
+
+impl<T, N, R> Debug for NormalizedObserver<T, N, R>
+where
+    T: Clone,
+    R: Recorder<T>,
+    OwnedObserver<T, R>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.observer.fmt(f)
+    }
+}
+
+impl<T, N> NormalizedObserver<T, N>
+where
+    T: Eq + Hash + Clone,
+{
+    /// Creates an observer which normalizes items with `normalizer`.
+    pub fn new(normalizer: N) -> Self {
+        Self::with_recorder(normalizer, HashMap::default())
+    }
+}
+
+impl<T, N, R> NormalizedObserver<T, N, R>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    /// Creates an observer which normalizes items with `normalizer`, and
+    /// stores its observations in `recorder`.
+    pub fn with_recorder(normalizer: N, recorder: R) -> Self {
+        Self {
+            observer: OwnedObserver::with_recorder(recorder),
+            normalizer,
+        }
+    }
+
+    /// Notices the normalized form of `item`.
+    #[track_caller]
+    pub fn notice<Q>(&self, item: &Q) -> Option<OwnedObservation<T, R>>
+    where
+        Q: ?Sized,
+        N: Normalizer<Q, T>,
+    {
+        self.observer.notice(self.normalizer.normalize(item))
+    }
+
+    /// Returns true if the normalized form of `item` is currently observed.
+    pub fn is_observing<Q>(&self, item: &Q) -> bool
+    where
+        Q: ?Sized,
+        N: Normalizer<Q, T>,
+    {
+        self.observer.observes(&self.normalizer.normalize(item))
+    }
+
+    /// Returns the number of items currently observed.
+    pub fn len(&self) -> usize {
+        self.observer.len()
+    }
+
+    /// Returns true if no items are currently observed.
+    pub fn is_empty(&self) -> bool {
+        self.observer.is_empty()
+    }
+
+    /// Returns the underlying observer, which sees the normalized items.
+    pub fn observer(&self) -> &OwnedObserver<T, R> {
+        &self.observer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn normalizes_before_noticing() {
+        let o = NormalizedObserver::new(|s: &str| s.to_lowercase());
+        let g = o.notice("A").unwrap();
+        assert_eq!(g.item(), "a");
+        assert!(o.notice("a").is_none());
+        drop(g);
+        assert!(o.notice("a").is_some());
+    }
+
+    #[test]
+    fn uses_custom_normalizer() {
+        struct Digits;
+        impl Normalizer<str, String> for Digits {
+            fn normalize(&self, item: &str) -> String {
+                item.chars().filter(char::is_ascii_digit).collect()
+            }
+        }
+        impl Normalizer<u32, String> for Digits {
+            fn normalize(&self, item: &u32) -> String {
+                alloc::format!("{}", item)
+            }
+        }
+
+        let o = NormalizedObserver::with_recorder(Digits, alloc::collections::BTreeSet::new());
+        let _g = o.notice("555-0100").unwrap();
+        assert!(o.is_observing(&5550100));
+        assert!(o.observer().is_observing("5550100"));
+    }
+}
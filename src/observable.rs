@@ -0,0 +1,209 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, Lookup, Observation, Observer, Recorder};
+use core::fmt::{self, Debug};
+use core::ops::Deref;
+
+/**
+An item that is identified by a key, rather than by its whole value.
+
+An [`Observer`] of the keys can observe such items with
+[`notice_observable`](Observer::notice_observable), so two different values
+with the same key count as the same item, and the item type itself needn't
+implement `Eq` or `Hash`.
+```
+use forgetful::{Observable, Observer};
+
+struct Module {
+    id: u32,
+    source: String,
+}
+
+impl Observable for Module {
+    type Key = u32;
+
+    fn key(&self) -> &u32 {
+        &self.id
+    }
+}
+
+let a = Module { id: 1, source: "a".to_string() };
+let stale = Module { id: 1, source: "old a".to_string() };
+let observer = Observer::new();
+let _a = observer.notice_observable(&a).expect("never seen before");
+assert!(observer.notice_observable(&stale).is_none());
+```
+*/
+pub trait Observable {
+    /// The type of key the item is identified by.
+    type Key: ?Sized;
+
+    /// Returns the item's key.
+    fn key(&self) -> &Self::Key;
+}
+
+impl<'a, K, R> Observer<'a, K, R>
+where
+    K: 'a + ?Sized,
+    R: Recorder<&'a K>,
+{
+    /// Notices `item` by its [`key`](Observable::key). The observation
+    /// holds the key, not the item.
+    #[track_caller]
+    pub fn notice_observable<O>(&self, item: &'a O) -> Option<Observation<'a, K, R>>
+    where
+        O: Observable<Key = K> + ?Sized,
+    {
+        self.notice(item.key())
+    }
+
+    /// Returns true if an item with the same key as `item` is currently
+    /// observed.
+    pub fn is_observing_observable<O>(&self, item: &O) -> bool
+    where
+        O: Observable<Key = K> + ?Sized,
+        R: Lookup<&'a K, K>,
+    {
+        self.is_observing(item.key())
+    }
+
+    /// Notices `item` by the key `key` projects from it. Unlike
+    /// [`notice_observable`](Self::notice_observable), the observation
+    /// still holds the whole item.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    ///
+    /// struct Job {
+    ///     id: u32,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// let build = Job { id: 1, name: "build" };
+    /// let again = Job { id: 1, name: "build again" };
+    /// let observer = Observer::new();
+    /// let running = observer.notice_by_key(&build, |job| &job.id).unwrap();
+    /// assert_eq!(running.name, "build");
+    /// assert!(observer.notice_by_key(&again, |job| &job.id).is_none());
+    /// ```
+    #[track_caller]
+    pub fn notice_by_key<O, F>(&self, item: &'a O, key: F) -> Option<KeyedObservation<'a, O, K, R>>
+    where
+        O: ?Sized,
+        F: FnOnce(&'a O) -> &'a K,
+    {
+        let observation = self.notice(key(item))?;
+        Some(KeyedObservation { item, observation })
+    }
+}
+
+/// An observation produced by [`Observer::notice_by_key`]: the observation
+/// of the key, together with the item it was projected from.
+pub struct KeyedObservation<'a, O, K, R = HashMap<&'a K, Entry, DefaultHashBuilder>>
+where
+    O: ?Sized,
+    K: 'a + ?Sized,
+    R: Recorder<&'a K>,
+{
+    item: &'a O,
+    observation: Observation<'a, K, R>,
+}
+
+impl<'a, O, K, R> Debug for KeyedObservation<'a, O, K, R>
+where
+    O: ?Sized + Debug,
+    K: 'a + ?Sized,
+    R: Recorder<&'a K>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.item)
+    }
+}
+
+impl<'a, O, K, R> KeyedObservation<'a, O, K, R>
+where
+    O: ?Sized,
+    K: 'a + ?Sized,
+    R: Recorder<&'a K>,
+{
+    /// Returns the observed item.
+    pub fn item(&self) -> &'a O {
+        self.item
+    }
+
+    /// Returns the key the item is observed by.
+    pub fn key(&self) -> &'a K {
+        self.observation.item()
+    }
+
+    /// Ends the observation now, rather than when it goes out of scope.
+    pub fn forget(self) {}
+
+    /// Returns the observation of the key, giving up the item.
+    pub fn into_observation(self) -> Observation<'a, K, R> {
+        self.observation
+    }
+}
+
+impl<'a, O, K, R> Deref for KeyedObservation<'a, O, K, R>
+where
+    O: ?Sized,
+    K: 'a + ?Sized,
+    R: Recorder<&'a K>,
+{
+    type Target = O;
+
+    fn deref(&self) -> &O {
+        self.item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Node {
+        id: &'static str,
+        weight: f64,
+    }
+
+    impl Observable for Node {
+        type Key = str;
+
+        fn key(&self) -> &str {
+            self.id
+        }
+    }
+
+    #[test]
+    fn dedups_by_key() {
+        let a = Node {
+            id: "a",
+            weight: 1.0,
+        };
+        let also_a = Node {
+            id: "a",
+            weight: 2.0,
+        };
+        let o = Observer::new();
+        let g = o.notice_observable(&a).unwrap();
+        assert_eq!(g.item(), "a");
+        assert!(o.is_observing_observable(&also_a));
+        assert!(o.notice_observable(&also_a).is_none());
+        assert!(a.weight < also_a.weight);
+    }
+
+    #[test]
+    fn keyed_observation_keeps_the_item() {
+        let a = Node {
+            id: "a",
+            weight: 1.0,
+        };
+        let o = Observer::new();
+        let g = o.notice_by_key(&a, |node| node.id).unwrap();
+        assert_eq!(g.weight, 1.0);
+        assert_eq!(g.key(), "a");
+        assert!(o.is_observing("a"));
+        drop(g);
+        assert!(o.is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{Iterable, Observer, Recorder};
+use alloc::vec::Vec;
+use core::cell::{Ref, RefCell};
+use core::fmt::{self, Debug};
+use core::iter::Copied;
+use core::marker::PhantomData;
+
+/**
+A view of the items an [`Observer`] is currently observing, returned by
+[`Observer::observed`].
+
+The view borrows the observer's recorder. While it is alive, the observer
+can't change: noticing an item, or dropping an observation, will panic.
+Keep it short-lived, e.g. for the duration of a loop.
+*/
+pub struct Observed<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+{
+    recorder: Ref<'o, R>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'o, 'a, T, R> Observed<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+{
+    pub(crate) fn new(recorder: Ref<'o, R>) -> Self {
+        Self {
+            recorder,
+            marker: PhantomData,
+        }
+    }
+
+    /// Iterates over the observed items, in the recorder's order.
+    pub fn iter(&self) -> Copied<R::Iter<'_>>
+    where
+        R: Iterable<&'a T>,
+    {
+        self.recorder.iter().copied()
+    }
+}
+
+impl<'v, 'o, 'a, T, R> IntoIterator for &'v Observed<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Iterable<&'a T>,
+{
+    type Item = &'a T;
+    type IntoIter = Copied<R::Iter<'v>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'o, 'a, T, R> Debug for Observed<'o, 'a, T, R>
+where
+    T: 'a + ?Sized + Debug,
+    R: Iterable<&'a T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "timestamps")]
+        {
+            let recorder = &self.recorder;
+            f.debug_set()
+                .entries(
+                    self.iter()
+                        .map(|item| crate::timestamps::Timed(item, recorder.entry(&item))),
+                )
+                .finish()
+        }
+        #[cfg(not(feature = "timestamps"))]
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Returns a view of the items currently observed.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let _foo = observer.notice("foo");
+    /// let _bar = observer.notice("bar");
+    /// let mut items: Vec<_> = observer.observed().iter().collect();
+    /// items.sort();
+    /// assert_eq!(items, ["bar", "foo"]);
+    /// ```
+    pub fn observed(&self) -> Observed<'_, 'a, T, R> {
+        Observed::new(RefCell::borrow(&self.shared.recorder))
+    }
+
+    /// Copies the items currently observed into a `Vec`.
+    ///
+    /// Unlike [`observed`](Self::observed), the result doesn't borrow the
+    /// observer, so it can be kept around while items are noticed and
+    /// forgotten.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let foo = observer.notice("foo");
+    /// let snapshot = observer.snapshot();
+    /// drop(foo);
+    /// let _bar = observer.notice("bar");
+    /// assert_eq!(snapshot, ["foo"]);
+    /// ```
+    pub fn snapshot(&self) -> Vec<&'a T>
+    where
+        R: Iterable<&'a T>,
+    {
+        self.observed().iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn yields_current_observations() {
+        let o = Observer::new();
+        let _g1 = o.notice(&1);
+        let g2 = o.notice(&2);
+        let mut items: Vec<_> = o.observed().iter().copied().collect();
+        items.sort_unstable();
+        assert_eq!(items, [1, 2]);
+        drop(g2);
+        let items: Vec<_> = (&o.observed()).into_iter().collect();
+        assert_eq!(items, [&1]);
+    }
+
+    #[test]
+    fn snapshot_is_decoupled_from_observer() {
+        let o = Observer::new();
+        let g = o.notice(&1);
+        let snapshot = o.snapshot();
+        drop(g);
+        let _g = o.notice(&2);
+        assert_eq!(snapshot, [&1]);
+        assert_eq!(o.snapshot(), [&2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_if_noticing_while_viewing() {
+        let o = Observer::new();
+        let observed = o.observed();
+        let _g = o.notice(&1);
+        drop(observed);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{Entry, Observation, Observer};
+use alloc::collections::BTreeMap;
+
+/**
+OrdObserver is an [`Observer`] backed by a `BTreeMap`, for items that are
+`Ord` but not `Hash`.
+```
+use forgetful::OrdObserver;
+
+// Orderable, but not Hash.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Version(u32, u32);
+
+let observer = OrdObserver::default();
+{
+    let observation = observer.notice(&Version(1, 2)).expect("never seen before");
+    assert!(observer.notice(&Version(1, 2)).is_none());
+}
+assert!(observer.notice(&Version(1, 2)).is_some());
+```
+*/
+pub type OrdObserver<'a, T> = Observer<'a, T, BTreeMap<&'a T, Entry>>;
+
+/// The observation type of [`OrdObserver`].
+pub type OrdObservation<'a, T> = Observation<'a, T, BTreeMap<&'a T, Entry>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Orderable, but deliberately not Hash.
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Version(u32, u32);
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = OrdObserver::default();
+        let g = o.notice(&Version(1, 5));
+        assert!(g.is_some());
+        assert!(o.notice(&Version(1, 5)).is_none());
+        drop(g);
+        assert!(o.notice(&Version(1, 5)).is_some());
+    }
+
+    #[test]
+    fn unique_items_noticed_independently() {
+        let o = OrdObserver::default();
+        let g1 = o.notice(&Version(1, 0));
+        let g2 = o.notice(&Version(2, 0));
+        assert!(g1.is_some());
+        assert!(g2.is_some());
+    }
+}
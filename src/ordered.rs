@@ -0,0 +1,338 @@
+use crate::hash::DefaultHashBuilder;
+use crate::{CycleError, Entry, Observation, Observer, OwnedObserver};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::hash::{BuildHasher, Hash, Hasher};
+use indexmap::map::raw_entry_v1::{RawEntryApiV1, RawEntryMut};
+use indexmap::{Equivalent, IndexMap};
+
+/**
+OrderedObserver is an [`Observer`] backed by an `IndexMap`, so it remembers
+the order in which its current observations were made. It is available with
+the `indexmap` feature.
+
+This is handy for diagnostics: in a recursive traversal, the observed items
+form the path from the root to the current node.
+```
+use forgetful::OrderedObserver;
+let observer = OrderedObserver::default();
+let a = observer.notice("a").expect("never seen before");
+let c = observer.notice("c").expect("never seen before");
+let b = observer.notice("b").expect("never seen before");
+assert_eq!(observer.snapshot(), ["a", "c", "b"]);
+drop(c);
+assert_eq!(observer.snapshot(), ["a", "b"]);
+```
+*/
+pub type OrderedObserver<'a, T> = Observer<'a, T, IndexMap<&'a T, Entry, DefaultHashBuilder>>;
+
+/// The observation type of [`OrderedObserver`].
+pub type OrderedObservation<'a, T> = Observation<'a, T, IndexMap<&'a T, Entry, DefaultHashBuilder>>;
+
+impl<'a, T, S> Observer<'a, T, IndexMap<&'a T, Entry, S>>
+where
+    T: 'a + Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    /// Returns the items currently observed, oldest first.
+    ///
+    /// In a recursive traversal, this is the path from the root to the
+    /// current node.
+    pub fn trail(&self) -> Vec<&'a T> {
+        RefCell::borrow(&self.shared.recorder)
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the [`trail`](Self::trail), with when each item was noticed,
+    /// or None for items that weren't noticed through
+    /// [`notice`](Observer::notice). Available with the `timestamps`
+    /// feature.
+    ///
+    /// ```
+    /// use forgetful::OrderedObserver;
+    /// let observer = OrderedObserver::default();
+    /// let _root = observer.notice("root");
+    /// let _a = observer.notice("a");
+    /// let trail = observer.timed_trail();
+    /// assert_eq!(trail[0].0, "root");
+    /// assert!(trail[0].1 <= trail[1].1);
+    /// ```
+    #[cfg(feature = "timestamps")]
+    pub fn timed_trail(&self) -> Vec<(&'a T, Option<std::time::Instant>)> {
+        RefCell::borrow(&self.shared.recorder)
+            .iter()
+            .map(|(item, entry)| (*item, entry.created()))
+            .collect()
+    }
+
+    /// Returns the part of the [`trail`](Self::trail) starting at `item`,
+    /// or None if `item` isn't observed.
+    ///
+    /// When noticing `item` fails in a traversal, this is the cycle that led
+    /// back to it.
+    ///
+    /// ```
+    /// use forgetful::OrderedObserver;
+    /// let observer = OrderedObserver::default();
+    /// let _root = observer.notice("root");
+    /// let _a = observer.notice("a");
+    /// let _b = observer.notice("b");
+    /// assert!(observer.notice("a").is_none());
+    /// assert_eq!(observer.trail_from("a").unwrap(), ["a", "b"]);
+    /// ```
+    pub fn trail_from(&self, item: &T) -> Option<Vec<&'a T>> {
+        let recorder = RefCell::borrow(&self.shared.recorder);
+        let start = recorder.get_index_of(item)?;
+        Some(recorder[start..].keys().copied().collect())
+    }
+
+    /// Returns true if an item equivalent to `query` is currently observed.
+    ///
+    /// Unlike [`is_observing`](Observer::is_observing), `query` needn't be
+    /// an item, or even borrowed from one: it only has to implement
+    /// [`Equivalent`] for the item type, and hash the same way. This lets a
+    /// composite item be looked up from borrowed parts.
+    ///
+    /// ```
+    /// use forgetful::{Equivalent, OrderedObserver};
+    ///
+    /// #[derive(Hash)]
+    /// struct Query<'q>(u32, &'q str);
+    ///
+    /// impl Equivalent<(u32, String)> for Query<'_> {
+    ///     fn equivalent(&self, key: &(u32, String)) -> bool {
+    ///         self.0 == key.0 && self.1 == key.1
+    ///     }
+    /// }
+    ///
+    /// let item = (1, "a".to_string());
+    /// let observer = OrderedObserver::default();
+    /// let _a = observer.notice(&item);
+    /// assert!(observer.is_observing_equivalent(&Query(1, "a")));
+    /// assert!(!observer.is_observing_equivalent(&Query(1, "b")));
+    /// ```
+    pub fn is_observing_equivalent<Q>(&self, query: &Q) -> bool
+    where
+        Q: Hash + Equivalent<T> + ?Sized,
+    {
+        RefCell::borrow(&self.shared.recorder).contains_key(&ByRef(query))
+    }
+
+    /// Like [`notice`](Observer::notice), but with the item's `hash` already
+    /// computed, so notices of large items don't pay to hash them.
+    /// Existing items are compared to `item` with `eq` rather than `Eq`.
+    ///
+    /// `hash` must be the hash the observer's [`hasher`](Self::hasher) gives
+    /// `item` (build a hasher, feed it the item, and `finish` it, as below),
+    /// and `eq` must agree with `Eq`; otherwise duplicates may be missed, and
+    /// the item may never be forgotten.
+    ///
+    /// Only the `IndexMap`-backed observer offers this: `std`'s `HashMap`,
+    /// behind the default [`Observer`], has no stable way to look an entry
+    /// up by a hash computed ahead of time.
+    ///
+    /// ```
+    /// use forgetful::OrderedObserver;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let observer = OrderedObserver::default();
+    /// let item = "a".repeat(1 << 16);
+    /// let mut hasher = observer.hasher().build_hasher();
+    /// item.hash(&mut hasher);
+    /// let hash = hasher.finish();
+    /// let _g = observer.notice_hashed(hash, |other| *other == item, &item).unwrap();
+    /// assert!(observer.notice_hashed(hash, |other| *other == item, &item).is_none());
+    /// ```
+    #[track_caller]
+    pub fn notice_hashed(
+        &self,
+        hash: u64,
+        eq: impl Fn(&T) -> bool,
+        item: &'a T,
+    ) -> Option<Observation<'a, T, IndexMap<&'a T, Entry, S>>> {
+        let mut recorder = self.shared.recorder.borrow_mut();
+        debug_assert_eq!(hash, {
+            let mut hasher = recorder.hasher().build_hasher();
+            item.hash(&mut hasher);
+            hasher.finish()
+        });
+        match recorder
+            .raw_entry_mut_v1()
+            .from_hash(hash, |other| eq(other))
+        {
+            RawEntryMut::Occupied(_) => {
+                drop(recorder);
+                self.shared.reject(&item);
+                None
+            }
+            RawEntryMut::Vacant(vacant) => {
+                vacant.insert_hashed_nocheck(hash, item, self.shared.entry());
+                drop(recorder);
+                Some(self.observation(item))
+            }
+        }
+    }
+
+    /// Returns the observer's hasher, for computing hashes to pass to
+    /// [`notice_hashed`](Self::notice_hashed).
+    pub fn hasher(&self) -> S
+    where
+        S: Clone,
+    {
+        RefCell::borrow(&self.shared.recorder).hasher().clone()
+    }
+}
+
+impl<T, S> OwnedObserver<T, IndexMap<T, Entry, S>>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    /// Returns true if an item equivalent to `query` is currently observed.
+    /// See [`Observer::is_observing_equivalent`].
+    pub fn is_observing_equivalent<Q>(&self, query: &Q) -> bool
+    where
+        Q: Hash + Equivalent<T> + ?Sized,
+    {
+        RefCell::borrow(&self.recorder).contains_key(query)
+    }
+}
+
+/// Adapts a query for `T` into a query for the `&T` keys of an [`Observer`].
+struct ByRef<'q, Q: ?Sized>(&'q Q);
+
+impl<Q: Hash + ?Sized> Hash for ByRef<'_, Q> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // `&T` hashes the same as `T`.
+        self.0.hash(state)
+    }
+}
+
+impl<Q, T> Equivalent<&T> for ByRef<'_, Q>
+where
+    Q: Equivalent<T> + ?Sized,
+    T: ?Sized,
+{
+    fn equivalent(&self, key: &&T) -> bool {
+        self.0.equivalent(*key)
+    }
+}
+
+impl<'a, T> OrderedObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Like [`notice`](Observer::notice), but reports a duplicate as a
+    /// [`CycleError`] holding the path that led back to the item.
+    ///
+    /// ```
+    /// use forgetful::OrderedObserver;
+    /// let observer = OrderedObserver::default();
+    /// let _a = observer.notice_or_cycle("a").unwrap();
+    /// let _b = observer.notice_or_cycle("b").unwrap();
+    /// let err = observer.notice_or_cycle("a").unwrap_err();
+    /// assert_eq!(err.to_string(), r#""a" -> "b" -> "a""#);
+    /// ```
+    #[track_caller]
+    pub fn notice_or_cycle(
+        &self,
+        item: &'a T,
+    ) -> Result<OrderedObservation<'a, T>, CycleError<'a, T>> {
+        match self.notice(item) {
+            Some(observation) => Ok(observation),
+            None => Err(CycleError::new(
+                self.trail_from(item).unwrap_or_default(),
+                item,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = OrderedObserver::default();
+        let g = o.notice(&1);
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[test]
+    fn keeps_insertion_order_across_forgets() {
+        let o = OrderedObserver::default();
+        let g3 = o.notice(&3);
+        let g1 = o.notice(&1);
+        let g2 = o.notice(&2);
+        drop(g3);
+        let g4 = o.notice(&4);
+        assert_eq!(o.snapshot(), [&1, &2, &4]);
+        #[cfg(not(feature = "timestamps"))]
+        assert_eq!(format!("{:?}", o), "{1, 2, 4}");
+        drop((g1, g2, g4));
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn trail_is_cut_at_the_requested_item() {
+        let o = OrderedObserver::default();
+        let _g1 = o.notice(&1);
+        let _g2 = o.notice(&2);
+        let _g3 = o.notice(&3);
+        assert_eq!(o.trail(), [&1, &2, &3]);
+        assert_eq!(o.trail_from(&2).unwrap(), [&2, &3]);
+        assert_eq!(o.trail_from(&4), None);
+    }
+
+    #[test]
+    fn cycle_error_holds_the_cycle() {
+        let o = OrderedObserver::default();
+        let _g0 = o.notice_or_cycle(&0).unwrap();
+        let _g1 = o.notice_or_cycle(&1).unwrap();
+        let _g2 = o.notice_or_cycle(&2).unwrap();
+        let err = o.notice_or_cycle(&1).unwrap_err();
+        assert_eq!(err.path(), [&1, &2, &1]);
+        assert_eq!(*err.item(), 1);
+        assert_eq!(err.to_string(), "1 -> 2 -> 1");
+    }
+
+    #[test]
+    fn looks_up_equivalent_items() {
+        struct Query<'q>(u32, &'q str);
+        impl Hash for Query<'_> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                (self.0, self.1).hash(state)
+            }
+        }
+        impl Equivalent<(u32, String)> for Query<'_> {
+            fn equivalent(&self, key: &(u32, String)) -> bool {
+                self.0 == key.0 && self.1 == key.1
+            }
+        }
+
+        let o = OwnedObserver::with_recorder(IndexMap::<_, _, DefaultHashBuilder>::default());
+        let _g = o.notice((1, "a".to_string()));
+        assert!(o.is_observing_equivalent(&Query(1, "a")));
+        assert!(!o.is_observing_equivalent(&Query(2, "a")));
+    }
+
+    #[test]
+    fn notices_prehashed_items() {
+        let o = OrderedObserver::default();
+        let hash = |item: &u32| {
+            let mut hasher = o.hasher().build_hasher();
+            item.hash(&mut hasher);
+            hasher.finish()
+        };
+        let g = o.notice_hashed(hash(&1), |other| *other == 1, &1).unwrap();
+        assert!(o.notice(&1).is_none());
+        assert!(o.notice_hashed(hash(&1), |other| *other == 1, &1).is_none());
+        drop(g);
+        assert!(o.is_empty());
+    }
+}
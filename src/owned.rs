@@ -0,0 +1,303 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, Iterable, Lookup, Recorder};
+use alloc::borrow::ToOwned;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::cmp::Eq;
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+/// An observation produced by [`OwnedObserver::notice`].
+///
+/// It holds its own copy of the item, so it isn't tied to any borrow.
+pub struct OwnedObservation<T, R = HashMap<T, Entry, DefaultHashBuilder>>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    item: T,
+    recorder: Rc<RefCell<R>>,
+}
+
+impl<T, R> Debug for OwnedObservation<T, R>
+where
+    T: Clone + Debug,
+    R: Recorder<T>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self.item)
+    }
+}
+
+impl<T, R> OwnedObservation<T, R>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    /// Returns the observed item.
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    /// Ends the observation now, rather than when it goes out of scope.
+    /// See [`Observation::forget`](crate::Observation::forget).
+    pub fn forget(self) {}
+}
+
+impl<T, R> Deref for OwnedObservation<T, R>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.item
+    }
+}
+
+impl<T, R> AsRef<T> for OwnedObservation<T, R>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    fn as_ref(&self) -> &T {
+        &self.item
+    }
+}
+
+impl<T, R> Drop for OwnedObservation<T, R>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    fn drop(&mut self) {
+        self.recorder.borrow_mut().remove(&self.item);
+    }
+}
+
+/**
+OwnedObserver is an [`Observer`](crate::Observer) that stores its items by
+value rather than by reference.
+
+It has no lifetime parameter, so it can be embedded in other structs freely
+and can notice temporaries. The cost is a clone of each item noticed.
+```
+use forgetful::OwnedObserver;
+let observer = OwnedObserver::new();
+{
+    let observation = observer.notice(String::from("foo")).expect("never seen before");
+    assert!(observer.notice("foo".to_string()).is_none());
+}
+assert!(observer.notice("foo".to_string()).is_some());
+```
+
+Like [`Observer`](crate::Observer), it can store its observations in any
+[`Recorder`]; see [`OwnedObserver::with_recorder`].
+*/
+pub struct OwnedObserver<T, R = HashMap<T, Entry, DefaultHashBuilder>>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    pub(crate) recorder: Rc<RefCell<R>>,
+    marker: PhantomData<T>,
+}
+
+impl<T, R> Default for OwnedObserver<T, R>
+where
+    T: Clone,
+    R: Recorder<T> + Default,
+{
+    fn default() -> Self {
+        Self::with_recorder(R::default())
+    }
+}
+
+impl<T, R> Debug for OwnedObserver<T, R>
+where
+    T: Clone + Debug,
+    R: Iterable<T>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set()
+            .entries(RefCell::borrow(&self.recorder).iter())
+            .finish()
+    }
+}
+
+impl<T> OwnedObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an observer that permanently observes `items`. See
+    /// [`Observer::with_blocked`](crate::Observer::with_blocked).
+    pub fn with_blocked<I>(items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        items.into_iter().collect()
+    }
+}
+
+impl<T, R> FromIterator<T> for OwnedObserver<T, R>
+where
+    T: Clone,
+    R: Recorder<T> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut recorder = R::default();
+        for item in items {
+            recorder.insert(item, Entry::default());
+        }
+        Self::with_recorder(recorder)
+    }
+}
+
+impl<T, R> OwnedObserver<T, R>
+where
+    T: Clone,
+    R: Recorder<T>,
+{
+    /// Creates an observer which stores its observations in `recorder`.
+    pub fn with_recorder(recorder: R) -> Self {
+        Self {
+            recorder: Rc::new(RefCell::new(recorder)),
+            marker: PhantomData,
+        }
+    }
+
+    #[track_caller]
+    pub fn notice(&self, item: T) -> Option<OwnedObservation<T, R>> {
+        // Check first, so duplicates don't pay for a clone.
+        if !RefCell::borrow(&self.recorder).can_insert(&item)
+            || !self
+                .recorder
+                .borrow_mut()
+                .insert(item.clone(), Entry::here())
+        {
+            None
+        } else {
+            Some(OwnedObservation {
+                item,
+                recorder: Rc::clone(&self.recorder),
+            })
+        }
+    }
+
+    /// Observes an owned copy of `item`, which may be any borrowed form of
+    /// the item type (a `&str` for `String` items, say). The copy is only
+    /// made if the item isn't already observed.
+    #[track_caller]
+    pub fn notice_ref<Q>(&self, item: &Q) -> Option<OwnedObservation<T, R>>
+    where
+        Q: ToOwned<Owned = T> + ?Sized,
+        R: Lookup<T, Q>,
+    {
+        if self.is_observing(item) {
+            None
+        } else {
+            self.notice(item.to_owned())
+        }
+    }
+
+    /// Returns true if `item` is currently observed. Like
+    /// `HashSet::contains`, `item` may be any borrowed form of the item type
+    /// that the recorder can look up.
+    pub fn is_observing<Q>(&self, item: &Q) -> bool
+    where
+        Q: ?Sized,
+        R: Lookup<T, Q>,
+    {
+        RefCell::borrow(&self.recorder).contains_ref(item)
+    }
+
+    /// Like [`is_observing`](Self::is_observing), for any recorder.
+    pub(crate) fn observes(&self, item: &T) -> bool {
+        RefCell::borrow(&self.recorder).contains(item)
+    }
+
+    /// Returns the number of items currently observed.
+    pub fn len(&self) -> usize {
+        RefCell::borrow(&self.recorder).len()
+    }
+
+    /// Returns true if no items are currently observed.
+    pub fn is_empty(&self) -> bool {
+        RefCell::borrow(&self.recorder).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forgets_immediately_with_no_observer() {
+        let o = OwnedObserver::new();
+        assert!(o.notice(1).is_some());
+        assert!(o.notice(1).is_some());
+    }
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = OwnedObserver::new();
+        let _g = o.notice(1);
+        assert!(o.notice(1).is_none());
+    }
+
+    #[test]
+    fn notices_temporaries() {
+        let o = OwnedObserver::new();
+        let g = o.notice(format!("{}-{}", "a", 1));
+        assert!(g.is_some());
+        assert!(o.notice("a-1".to_string()).is_none());
+    }
+
+    #[test]
+    fn uses_custom_recorder() {
+        let o = OwnedObserver::with_recorder(alloc::collections::BTreeSet::new());
+        let _g = o.notice(1.to_string());
+        assert!(o.notice("1".to_string()).is_none());
+    }
+
+    #[test]
+    fn refused_items_are_not_observed() {
+        let o = OwnedObserver::with_recorder(crate::WeightedRecorder::new(1));
+        let _g = o.notice(1).unwrap();
+        assert!(o.notice(2).is_none());
+        assert_eq!(o.len(), 1);
+    }
+
+    #[test]
+    fn can_be_embedded_without_a_lifetime() {
+        #[derive(Default)]
+        struct Walker {
+            seen: OwnedObserver<u32>,
+        }
+        let w = Walker::default();
+        let _g = w.seen.notice(7);
+        assert!(w.seen.notice(7).is_none());
+    }
+
+    #[test]
+    fn looks_up_by_borrowed_form() {
+        let o: OwnedObserver<String> = OwnedObserver::new();
+        let g = o.notice_ref("a").unwrap();
+        assert_eq!(g.item(), "a");
+        assert!(o.is_observing("a"));
+        assert!(o.notice_ref("a").is_none());
+        drop(g);
+        assert!(!o.is_observing("a"));
+
+        let o = OwnedObserver::with_recorder(alloc::collections::BTreeSet::<String>::new());
+        let _g = o.notice_ref("b").unwrap();
+        assert!(o.is_observing("b") && o.notice_ref("b").is_none());
+    }
+}
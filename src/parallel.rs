@@ -0,0 +1,101 @@
+use crate::{SyncObservation, SyncObserver};
+use core::cmp::Eq;
+use core::hash::Hash;
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::iter::ParallelIterator;
+
+/// Extends rayon's [`ParallelIterator`]s with
+/// [`filter_unobserved`](Self::filter_unobserved). It is available with the
+/// `rayon` feature.
+pub trait ParallelIteratorExt: ParallelIterator {
+    /**
+    Filters out items that are currently observed by `observer`, and yields
+    the rest together with their observation, in parallel.
+
+    Each check-and-record is atomic, so when several threads reach the same
+    item only one of them gets its observation. The observations are `Send`,
+    so they can be held inside later parallel closures, passed between
+    threads, and dropped wherever the work on an item finishes.
+    ```
+    use forgetful::{ParallelIteratorExt, SyncObserver};
+    use rayon::prelude::*;
+
+    let observer = SyncObserver::new();
+    let jobs = [1, 2, 1, 3, 2, 1];
+    let claimed: Vec<_> = jobs.par_iter().filter_unobserved(&observer).collect();
+    // Every job is claimed exactly once while the observations are held.
+    let mut ids: Vec<_> = claimed.iter().map(|(job, _)| **job).collect();
+    ids.sort();
+    assert_eq!(ids, [1, 2, 3]);
+    ```
+    */
+    fn filter_unobserved<'o, 'a, T>(
+        self,
+        observer: &'o SyncObserver<'a, T>,
+    ) -> FilterUnobserved<'o, 'a, Self, T>
+    where
+        Self: ParallelIterator<Item = &'a T>,
+        T: 'a + Eq + Hash + Sync + ?Sized,
+    {
+        FilterUnobserved {
+            iter: self,
+            observer,
+        }
+    }
+}
+
+impl<I> ParallelIteratorExt for I where I: ParallelIterator {}
+
+/// The parallel iterator returned by
+/// [`ParallelIteratorExt::filter_unobserved`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct FilterUnobserved<'o, 'a, I, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    iter: I,
+    observer: &'o SyncObserver<'a, T>,
+}
+
+impl<'o, 'a, I, T> ParallelIterator for FilterUnobserved<'o, 'a, I, T>
+where
+    I: ParallelIterator<Item = &'a T>,
+    T: 'a + Eq + Hash + Sync + ?Sized,
+{
+    type Item = (&'a T, SyncObservation<'a, T>);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let observer = self.observer;
+        self.iter
+            .filter_map(move |item| observer.notice(item).map(|observation| (item, observation)))
+            .drive_unindexed(consumer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn each_item_is_claimed_once_and_worked_on_in_parallel() {
+        let o = SyncObserver::new();
+        let items: Vec<u32> = (0..1000).map(|n| n % 10).collect();
+        let worked = AtomicUsize::new(0);
+        let claimed: Vec<_> = items.par_iter().filter_unobserved(&o).collect();
+        assert_eq!(claimed.len(), 10);
+        // Hand the observations to another parallel stage, which releases
+        // each one when it's done.
+        claimed.into_par_iter().for_each(|(item, observation)| {
+            assert!(o.notice(item).is_none());
+            worked.fetch_add(1, Ordering::Relaxed);
+            drop(observation);
+        });
+        assert_eq!(worked.into_inner(), 10);
+        assert!(o.notice(&0).is_some());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{OwnedObservation, OwnedObserver, SymlinkCycle};
+use std::fmt::{self, Debug};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// How a [`PathObserver`] resolves paths before comparing them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Resolve {
+    /// Canonicalize paths with [`std::fs::canonicalize`], resolving
+    /// symlinks. The paths must exist.
+    #[default]
+    Symlinks,
+    /// Make paths absolute and remove `.` and `..` components, without
+    /// touching the filesystem. Symlinks are compared as they are.
+    Lexically,
+}
+
+impl Resolve {
+    fn resolve(self, path: &Path) -> io::Result<PathBuf> {
+        match self {
+            Resolve::Symlinks => path.canonicalize(),
+            Resolve::Lexically => {
+                let mut resolved = PathBuf::new();
+                for component in std::env::current_dir()?.join(path).components() {
+                    match component {
+                        Component::CurDir => {}
+                        Component::ParentDir => {
+                            resolved.pop();
+                        }
+                        component => resolved.push(component),
+                    }
+                }
+                Ok(resolved)
+            }
+        }
+    }
+}
+
+/**
+PathObserver is an [`OwnedObserver`] of paths, which resolves each path
+before comparing it, so that different spellings of a path are observed as
+the same path.
+
+By default it canonicalizes paths, following symlinks; see [`Resolve`] for
+the alternative. Since that needs the filesystem, its methods return
+[`io::Result`]s.
+
+In a directory walker that follows symlinks, [`enter`](Self::enter) each
+directory before reading it, and a symlink back up the tree is reported as a
+[`SymlinkCycle`]:
+```
+use forgetful::PathObserver;
+use std::{fs, io, path::Path};
+
+fn walk(dir: &Path, walking: &PathObserver, files: &mut usize) -> io::Result<()> {
+    let _walking = walking.enter(dir)?;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, walking, files)?;
+        } else {
+            *files += 1;
+        }
+    }
+    Ok(())
+}
+
+let mut files = 0;
+walk(Path::new("src"), &PathObserver::new(), &mut files).unwrap();
+assert!(files > 0);
+```
+*/
+#[derive(Default)]
+pub struct PathObserver {
+    observer: OwnedObserver<PathBuf>,
+    resolve: Resolve,
+}
+
+impl Debug for PathObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.observer.fmt(f)
+    }
+}
+
+impl PathObserver {
+    /// Creates an observer which canonicalizes paths.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an observer which resolves paths as `resolve` says.
+    pub fn with_resolve(resolve: Resolve) -> Self {
+        Self {
+            observer: OwnedObserver::new(),
+            resolve,
+        }
+    }
+
+    /// Returns the path `path` is compared as.
+    pub fn resolve(&self, path: impl AsRef<Path>) -> io::Result<PathBuf> {
+        self.resolve.resolve(path.as_ref())
+    }
+
+    /// Notices the resolved form of `path`. The observation holds the
+    /// resolved path.
+    #[track_caller]
+    pub fn notice(&self, path: impl AsRef<Path>) -> io::Result<Option<OwnedObservation<PathBuf>>> {
+        Ok(self.observer.notice(self.resolve(path)?))
+    }
+
+    /// Notices the directory `dir` on the way into it, failing with a
+    /// [`SymlinkCycle`] (wrapped in an [`io::Error`], so walkers can use `?`)
+    /// if it is already being walked.
+    #[track_caller]
+    pub fn enter(&self, dir: impl AsRef<Path>) -> io::Result<OwnedObservation<PathBuf>> {
+        let dir = dir.as_ref();
+        let target = self.resolve(dir)?;
+        match self.observer.notice(target.clone()) {
+            Some(observation) => Ok(observation),
+            None => Err(io::Error::new(
+                io::ErrorKind::Other,
+                SymlinkCycle::new(dir.to_path_buf(), target),
+            )),
+        }
+    }
+
+    /// Returns true if the resolved form of `path` is currently observed.
+    pub fn is_observing(&self, path: impl AsRef<Path>) -> io::Result<bool> {
+        Ok(self.observer.is_observing(&self.resolve(path)?))
+    }
+
+    /// Returns the number of paths currently observed.
+    pub fn len(&self) -> usize {
+        self.observer.len()
+    }
+
+    /// Returns true if no paths are currently observed.
+    pub fn is_empty(&self) -> bool {
+        self.observer.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_lexically() {
+        let o = PathObserver::with_resolve(Resolve::Lexically);
+        let _g = o.notice("a/./b/../c").unwrap().unwrap();
+        assert!(o.is_observing("a/c").unwrap());
+        assert!(o.notice("./a/c").unwrap().is_none());
+    }
+
+    #[test]
+    fn canonicalizes_existing_paths() {
+        let o = PathObserver::new();
+        let _g = o.notice("src").unwrap().unwrap();
+        assert!(o.is_observing("src/../src/.").unwrap());
+        assert!(o.notice("missing/path").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detects_symlink_cycles() {
+        let root = std::env::temp_dir().join(format!("forgetful-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let link = root.join("loop");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&root, &link).unwrap();
+
+        let o = PathObserver::new();
+        let _root = o.enter(&root).unwrap();
+        let err = o.enter(&link).unwrap_err();
+        let cycle = err
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<SymlinkCycle>()
+            .unwrap();
+        assert_eq!(cycle.dir(), link);
+        assert_eq!(cycle.target(), root.canonicalize().unwrap());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
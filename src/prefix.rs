@@ -0,0 +1,286 @@
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt::{self, Debug};
+
+/// Which observed paths a [`PrefixObserver`] lets a new path conflict with,
+/// besides the path itself.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Conflicts {
+    /// Paths are blocked by their observed ancestors: with `a` observed,
+    /// `a/b` can't be noticed.
+    #[default]
+    Ancestors,
+    /// Paths are blocked by their observed ancestors and descendants: with
+    /// `a/b` observed, `a` can't be noticed either.
+    AncestorsAndDescendants,
+}
+
+/// A node of the trie of observed paths.
+struct Node<S> {
+    observed: bool,
+    /// The number of observed paths strictly below this node.
+    below: usize,
+    children: BTreeMap<S, Node<S>>,
+}
+
+impl<S> Default for Node<S> {
+    fn default() -> Self {
+        Self {
+            observed: false,
+            below: 0,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl<S: Ord> Node<S> {
+    /// Returns true if a path through this node, with `path` left to go, is
+    /// blocked by an observed path.
+    fn blocks(&self, path: &[S], conflicts: Conflicts) -> bool {
+        if self.observed {
+            return true;
+        }
+        match path.split_first() {
+            Some((segment, rest)) => self
+                .children
+                .get(segment)
+                .is_some_and(|child| child.blocks(rest, conflicts)),
+            None => conflicts == Conflicts::AncestorsAndDescendants && self.below > 0,
+        }
+    }
+
+    fn insert(&mut self, path: &[S])
+    where
+        S: Clone,
+    {
+        match path.split_first() {
+            Some((segment, rest)) => {
+                self.below += 1;
+                self.children
+                    .entry(segment.clone())
+                    .or_default()
+                    .insert(rest);
+            }
+            None => self.observed = true,
+        }
+    }
+
+    fn remove(&mut self, path: &[S]) {
+        match path.split_first() {
+            Some((segment, rest)) => {
+                let child = match self.children.get_mut(segment) {
+                    Some(child) => child,
+                    None => return,
+                };
+                child.remove(rest);
+                self.below -= 1;
+                if !child.observed && child.children.is_empty() {
+                    self.children.remove(segment);
+                }
+            }
+            None => self.observed = false,
+        }
+    }
+
+    fn get(&self, path: &[S]) -> Option<&Self> {
+        match path.split_first() {
+            Some((segment, rest)) => self.children.get(segment)?.get(rest),
+            None => Some(self),
+        }
+    }
+
+    fn collect<'n>(&'n self, path: &mut Vec<&'n S>, paths: &mut Vec<Vec<&'n S>>) {
+        if self.observed {
+            paths.push(path.clone());
+        }
+        for (segment, child) in &self.children {
+            path.push(segment);
+            child.collect(path, paths);
+            path.pop();
+        }
+    }
+}
+
+/// An observation produced by [`PrefixObserver::notice`].
+pub struct PrefixObservation<S>
+where
+    S: Ord + Clone,
+{
+    path: Vec<S>,
+    root: Rc<RefCell<Node<S>>>,
+}
+
+impl<S> Debug for PrefixObservation<S>
+where
+    S: Ord + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.path)
+    }
+}
+
+impl<S> PrefixObservation<S>
+where
+    S: Ord + Clone,
+{
+    /// Returns the observed path.
+    pub fn path(&self) -> &[S] {
+        &self.path
+    }
+
+    /// Ends the observation now, rather than when it goes out of scope.
+    pub fn forget(self) {}
+}
+
+impl<S> Drop for PrefixObservation<S>
+where
+    S: Ord + Clone,
+{
+    fn drop(&mut self) {
+        self.root.borrow_mut().remove(&self.path);
+    }
+}
+
+/**
+PrefixObserver observes paths of segments, such as directories or
+namespaces, and treats a path as taken while any of its ancestors is
+observed. Optionally, a path is also taken while any of its descendants is
+observed; see [`Conflicts`].
+
+The observed paths are kept in a trie, so noticing a path takes time in
+proportion to its length, however many paths are observed.
+```
+use forgetful::PrefixObserver;
+let observer = PrefixObserver::new();
+let src = observer.notice(&["src"]).expect("never seen before");
+assert!(observer.notice(&["src", "lib.rs"]).is_none());
+assert!(observer.notice(&["tests", "it.rs"]).is_some());
+drop(src);
+assert!(observer.notice(&["src", "lib.rs"]).is_some());
+```
+*/
+pub struct PrefixObserver<S>
+where
+    S: Ord + Clone,
+{
+    root: Rc<RefCell<Node<S>>>,
+    conflicts: Conflicts,
+}
+
+impl<S> Default for PrefixObserver<S>
+where
+    S: Ord + Clone,
+{
+    fn default() -> Self {
+        Self::with_conflicts(Conflicts::default())
+    }
+}
+
+impl<S> Debug for PrefixObserver<S>
+where
+    S: Ord + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let root = self.root.borrow();
+        let mut paths = Vec::new();
+        root.collect(&mut Vec::new(), &mut paths);
+        f.debug_set().entries(paths).finish()
+    }
+}
+
+impl<S> PrefixObserver<S>
+where
+    S: Ord + Clone,
+{
+    /// Creates an observer in which paths conflict with their ancestors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an observer in which paths conflict as `conflicts` says.
+    ///
+    /// ```
+    /// use forgetful::{Conflicts, PrefixObserver};
+    /// let observer = PrefixObserver::with_conflicts(Conflicts::AncestorsAndDescendants);
+    /// let _lib = observer.notice(&["src", "lib.rs"]).expect("never seen before");
+    /// assert!(observer.notice(&["src"]).is_none());
+    /// ```
+    pub fn with_conflicts(conflicts: Conflicts) -> Self {
+        Self {
+            root: Rc::default(),
+            conflicts,
+        }
+    }
+
+    /// Observes `path`, unless it is observed already or conflicts with an
+    /// observed path.
+    pub fn notice(&self, path: &[S]) -> Option<PrefixObservation<S>> {
+        let mut root = self.root.borrow_mut();
+        if root.blocks(path, self.conflicts) {
+            return None;
+        }
+        root.insert(path);
+        Some(PrefixObservation {
+            path: path.to_vec(),
+            root: Rc::clone(&self.root),
+        })
+    }
+
+    /// Returns true if noticing `path` now would fail.
+    pub fn is_blocked(&self, path: &[S]) -> bool {
+        self.root.borrow().blocks(path, self.conflicts)
+    }
+
+    /// Returns true if `path` itself is currently observed.
+    pub fn is_observing(&self, path: &[S]) -> bool {
+        self.root
+            .borrow()
+            .get(path)
+            .is_some_and(|node| node.observed)
+    }
+
+    /// Returns the number of paths currently observed.
+    pub fn len(&self) -> usize {
+        let root = self.root.borrow();
+        root.below + usize::from(root.observed)
+    }
+
+    /// Returns true if no paths are currently observed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ancestors_block_descendants() {
+        let o = PrefixObserver::new();
+        let _ab = o.notice(&[1, 2]).unwrap();
+        assert!(o.notice(&[1, 2]).is_none());
+        assert!(o.notice(&[1, 2, 3]).is_none());
+        let a = o.notice(&[1]).unwrap();
+        assert!(o.is_blocked(&[1, 4]));
+        drop(a);
+        assert!(o.notice(&[1, 4]).is_some());
+        assert_eq!(format!("{:?}", o), "{[1, 2]}");
+    }
+
+    #[test]
+    fn descendants_can_block_ancestors() {
+        let o = PrefixObserver::with_conflicts(Conflicts::AncestorsAndDescendants);
+        let abc = o.notice(&["a", "b", "c"]).unwrap();
+        assert!(o.notice(&["a"]).is_none());
+        assert!(o.notice(&[]).is_none());
+        assert!(o.notice(&["a", "c"]).is_some());
+        drop(abc);
+        assert!(o.is_empty());
+        let _a = o.notice(&["a"]).unwrap();
+        assert!(o.is_observing(&["a"]));
+        assert!(!o.is_observing(&["a", "b"]));
+    }
+}
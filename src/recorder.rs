@@ -0,0 +1,611 @@
+use crate::hash::{hash_map, hash_set, HashMap, HashSet};
+use alloc::borrow::Borrow;
+use alloc::collections::{btree_map, btree_set, BTreeMap, BTreeSet};
+use core::cmp::{Eq, Ord};
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "track-caller")]
+use core::panic::Location;
+
+/// What an observer knows about one of its observations.
+///
+/// A [`Recorder`] is handed an entry along with each item it records. Map
+/// based recorders, such as the default `HashMap`, keep it so it can be
+/// reported later, e.g. by [`DuplicateError`](crate::DuplicateError); set
+/// based ones discard it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Entry {
+    #[cfg(feature = "track-caller")]
+    location: Option<&'static Location<'static>>,
+    #[cfg(feature = "timestamps")]
+    created: Option<std::time::Instant>,
+    generation: u64,
+}
+
+impl Entry {
+    /// Creates the entry for an observation made by the caller.
+    #[track_caller]
+    pub(crate) fn here() -> Self {
+        Self {
+            #[cfg(feature = "track-caller")]
+            location: Some(Location::caller()),
+            #[cfg(feature = "timestamps")]
+            created: Some(std::time::Instant::now()),
+            generation: 0,
+        }
+    }
+
+    /// Returns the entry, as made in the observer's `generation`.
+    pub(crate) fn in_generation(mut self, generation: u64) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Returns where the item was noticed, or None if it wasn't noticed
+    /// through [`Observer::notice`](crate::Observer::notice), e.g. because
+    /// it was passed to [`Observer::with_blocked`](crate::Observer::with_blocked).
+    ///
+    /// Available with the `track-caller` feature.
+    #[cfg(feature = "track-caller")]
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        self.location
+    }
+
+    /// Returns when the item was noticed, or None if it wasn't noticed
+    /// through [`Observer::notice`](crate::Observer::notice).
+    ///
+    /// Available with the `timestamps` feature.
+    #[cfg(feature = "timestamps")]
+    pub fn created(&self) -> Option<std::time::Instant> {
+        self.created
+    }
+
+    /// Returns the observer's generation when the item was noticed, i.e.
+    /// how many items it had noticed, counting this one; see
+    /// [`Observer::find_leaked`](crate::Observer::find_leaked). It is 0 if
+    /// the item wasn't noticed through an [`Observer`](crate::Observer).
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+/**
+Recorder is the storage behind an [`Observer`](crate::Observer): the items
+currently observed, each with its [`Entry`].
+
+It is implemented for `HashMap` (the default), `BTreeMap`, and, with the
+`indexmap` feature, `IndexMap`, which keep entries, and for the matching set
+types, which don't. Implement it to plug in your own store:
+```
+use forgetful::{Entry, Observer, Recorder};
+
+// A store for a handful of items, where a linear scan beats hashing.
+#[derive(Default)]
+struct Small<K>(Vec<K>);
+
+impl<K: PartialEq> Recorder<K> for Small<K> {
+    fn insert(&mut self, item: K, _entry: Entry) -> bool {
+        if self.contains(&item) {
+            return false;
+        }
+        self.0.push(item);
+        true
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        match self.0.iter().position(|k| k == item) {
+            Some(i) => {
+                self.0.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        self.0.contains(item)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+}
+
+let observer = Observer::with_recorder(Small::default());
+let _observation = observer.notice("foo").expect("never seen before");
+assert!(observer.notice("foo").is_none());
+```
+
+To be queried with [`Observer::is_observing`](crate::Observer::is_observing),
+a recorder also implements [`Lookup`].
+*/
+pub trait Recorder<K> {
+    /// Records `item` along with `entry`, returning false if it was already
+    /// recorded, or if the recorder refuses it, e.g. for lack of room, in
+    /// which case it isn't recorded at all.
+    fn insert(&mut self, item: K, entry: Entry) -> bool;
+
+    /// Forgets `item`, returning false if it wasn't recorded.
+    fn remove(&mut self, item: &K) -> bool;
+
+    /// Forgets `item` however many times it is recorded, returning false if
+    /// it wasn't recorded. [`Observer::retain`](crate::Observer::retain)
+    /// uses this to forget items outright.
+    ///
+    /// Recorders that let an item be recorded more than once should forget
+    /// every record of it here; the default removes it.
+    fn remove_all(&mut self, item: &K) -> bool {
+        self.remove(item)
+    }
+
+    /// Undoes the insert of `item`, as if it had never been recorded,
+    /// returning false if it wasn't recorded. Rollbacks, such as a failed
+    /// [`Observer::notice_all`](crate::Observer::notice_all), use this
+    /// rather than [`remove`](Self::remove).
+    ///
+    /// Recorders that keep remembering removed items should forget `item`
+    /// outright here; the default removes it.
+    fn undo_insert(&mut self, item: &K) -> bool {
+        self.remove(item)
+    }
+
+    /// Returns true if `item` is recorded.
+    fn contains(&self, item: &K) -> bool;
+
+    /// Returns true if inserting `item` now would succeed.
+    ///
+    /// This is usually the same as not containing it, but a recorder may
+    /// allow an item to be recorded more than once, or refuse items.
+    fn can_insert(&self, item: &K) -> bool {
+        !self.contains(item)
+    }
+
+    /// Returns the entry recorded with `item`, or None if it isn't recorded
+    /// or the recorder doesn't keep entries.
+    fn entry(&self, _item: &K) -> Option<&Entry> {
+        None
+    }
+
+    /// Returns the number of items recorded.
+    fn len(&self) -> usize;
+
+    /// Returns true if no items are recorded.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets every item.
+    fn clear(&mut self);
+
+    /// Sets the [`generation`](Entry::generation) of every entry to 0, as
+    /// for items that weren't noticed through the observer, e.g. when the
+    /// recorder has been copied to a new one by
+    /// [`Observer::duplicate`](crate::Observer::duplicate).
+    ///
+    /// Recorders that keep entries should implement this; the default does
+    /// nothing.
+    fn reset_generations(&mut self) {}
+}
+
+/// A [`Recorder`] whose items can be listed, which is needed to view or
+/// serialize an observer's observations.
+pub trait Iterable<K>: Recorder<K> {
+    /// The iterator returned by [`iter`](Self::iter).
+    type Iter<'r>: Iterator<Item = &'r K>
+    where
+        Self: 'r,
+        K: 'r;
+
+    /// Iterates over the recorded items.
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+/**
+A [`Recorder`] that can look up its items by a borrowed form `Q`, as
+`HashSet::contains` does, which is needed to query an observer without
+borrowing for as long as its items.
+
+An [`OwnedObserver`](crate::OwnedObserver) may be queried by any borrowed
+form of its items, e.g. by `str` for `String` items. The items of an
+[`Observer`](crate::Observer) are references, `&'a T`, and it is queried by
+`T` through a reference of any lifetime.
+
+The recorders in this crate implement it for every `Q` their items borrow as.
+*/
+pub trait Lookup<K, Q>: Recorder<K>
+where
+    Q: ?Sized,
+{
+    /// Returns true if the item `item` is borrowed from is recorded.
+    fn contains_ref(&self, item: &Q) -> bool;
+
+    /// Returns the entry recorded with the item `item` is borrowed from, or
+    /// None if it isn't recorded or the recorder doesn't keep entries.
+    fn entry_ref(&self, _item: &Q) -> Option<&Entry> {
+        None
+    }
+}
+
+impl<K, S> Recorder<K> for HashMap<K, Entry, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        match HashMap::entry(self, item) {
+            hash_map::Entry::Occupied(_) => false,
+            hash_map::Entry::Vacant(vacant) => {
+                vacant.insert(entry);
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        HashMap::remove(self, item).is_some()
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        HashMap::contains_key(self, item)
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        HashMap::get(self, item)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+
+    fn reset_generations(&mut self) {
+        for entry in self.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+impl<K, S> Iterable<K> for HashMap<K, Entry, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = hash_map::Keys<'r, K, Entry>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.keys()
+    }
+}
+
+impl<K, Q, S> Lookup<K, Q> for HashMap<K, Entry, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        HashMap::contains_key(self, item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        HashMap::get(self, item)
+    }
+}
+
+impl<K, S> Recorder<K> for HashSet<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, _entry: Entry) -> bool {
+        HashSet::insert(self, item)
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        HashSet::remove(self, item)
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        HashSet::contains(self, item)
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        HashSet::clear(self)
+    }
+}
+
+impl<K, S> Iterable<K> for HashSet<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = hash_set::Iter<'r, K>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashSet::iter(self)
+    }
+}
+
+impl<K, Q, S> Lookup<K, Q> for HashSet<K, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        HashSet::contains(self, item)
+    }
+}
+
+impl<K> Recorder<K> for BTreeSet<K>
+where
+    K: Ord,
+{
+    fn insert(&mut self, item: K, _entry: Entry) -> bool {
+        BTreeSet::insert(self, item)
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        BTreeSet::remove(self, item)
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        BTreeSet::contains(self, item)
+    }
+
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        BTreeSet::clear(self)
+    }
+}
+
+impl<K> Iterable<K> for BTreeSet<K>
+where
+    K: Ord,
+{
+    type Iter<'r>
+        = btree_set::Iter<'r, K>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeSet::iter(self)
+    }
+}
+
+impl<K, Q> Lookup<K, Q> for BTreeSet<K>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        BTreeSet::contains(self, item)
+    }
+}
+
+impl<K> Recorder<K> for BTreeMap<K, Entry>
+where
+    K: Ord,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        match BTreeMap::entry(self, item) {
+            btree_map::Entry::Occupied(_) => false,
+            btree_map::Entry::Vacant(vacant) => {
+                vacant.insert(entry);
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        BTreeMap::remove(self, item).is_some()
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        BTreeMap::contains_key(self, item)
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        BTreeMap::get(self, item)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn clear(&mut self) {
+        BTreeMap::clear(self)
+    }
+
+    fn reset_generations(&mut self) {
+        for entry in self.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+impl<K> Iterable<K> for BTreeMap<K, Entry>
+where
+    K: Ord,
+{
+    type Iter<'r>
+        = btree_map::Keys<'r, K, Entry>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.keys()
+    }
+}
+
+impl<K, Q> Lookup<K, Q> for BTreeMap<K, Entry>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        BTreeMap::contains_key(self, item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        BTreeMap::get(self, item)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, S> Recorder<K> for indexmap::IndexSet<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, _entry: Entry) -> bool {
+        indexmap::IndexSet::insert(self, item)
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        // Shifting keeps the remaining items in order. Observations usually
+        // end in the reverse order they started, making this a cheap pop.
+        self.shift_remove(item)
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        indexmap::IndexSet::contains(self, item)
+    }
+
+    fn len(&self) -> usize {
+        indexmap::IndexSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        indexmap::IndexSet::clear(self)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, S> Iterable<K> for indexmap::IndexSet<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = indexmap::set::Iter<'r, K>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        indexmap::IndexSet::iter(self)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, Q, S> Lookup<K, Q> for indexmap::IndexSet<K, S>
+where
+    K: Eq + Hash,
+    Q: Hash + indexmap::Equivalent<K> + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        indexmap::IndexSet::contains(self, item)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, S> Recorder<K> for indexmap::IndexMap<K, Entry, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        match indexmap::IndexMap::entry(self, item) {
+            indexmap::map::Entry::Occupied(_) => false,
+            indexmap::map::Entry::Vacant(vacant) => {
+                vacant.insert(entry);
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        // See the IndexSet implementation.
+        self.shift_remove(item).is_some()
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        indexmap::IndexMap::contains_key(self, item)
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        indexmap::IndexMap::get(self, item)
+    }
+
+    fn len(&self) -> usize {
+        indexmap::IndexMap::len(self)
+    }
+
+    fn clear(&mut self) {
+        indexmap::IndexMap::clear(self)
+    }
+
+    fn reset_generations(&mut self) {
+        for entry in self.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, S> Iterable<K> for indexmap::IndexMap<K, Entry, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = indexmap::map::Keys<'r, K, Entry>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.keys()
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, Q, S> Lookup<K, Q> for indexmap::IndexMap<K, Entry, S>
+where
+    K: Eq + Hash,
+    Q: Hash + indexmap::Equivalent<K> + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        indexmap::IndexMap::contains_key(self, item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        indexmap::IndexMap::get(self, item)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{Entry, Lookup, OwnedObservation, OwnedObserver, Recorder};
+use ::roaring::RoaringTreemap;
+
+impl Recorder<u64> for RoaringTreemap {
+    fn insert(&mut self, item: u64, _entry: Entry) -> bool {
+        RoaringTreemap::insert(self, item)
+    }
+
+    fn remove(&mut self, item: &u64) -> bool {
+        RoaringTreemap::remove(self, *item)
+    }
+
+    fn contains(&self, item: &u64) -> bool {
+        RoaringTreemap::contains(self, *item)
+    }
+
+    fn len(&self) -> usize {
+        RoaringTreemap::len(self) as usize
+    }
+
+    fn clear(&mut self) {
+        RoaringTreemap::clear(self)
+    }
+}
+
+impl Lookup<u64, u64> for RoaringTreemap {
+    fn contains_ref(&self, item: &u64) -> bool {
+        RoaringTreemap::contains(self, *item)
+    }
+}
+
+/**
+RoaringObserver is an [`OwnedObserver`] for `u64` keys backed by a
+compressed [`RoaringTreemap`], available with the `roaring` feature.
+
+For large numbers of sparse ids, this takes a fraction of the memory of a
+`HashMap`.
+```
+use forgetful::RoaringObserver;
+let observer = RoaringObserver::default();
+{
+    let observation = observer.notice(1 << 40).expect("never seen before");
+    assert!(observer.notice(1 << 40).is_none());
+}
+assert!(observer.notice(1 << 40).is_some());
+```
+*/
+pub type RoaringObserver = OwnedObserver<u64, RoaringTreemap>;
+
+/// The observation type of [`RoaringObserver`].
+pub type RoaringObservation = OwnedObservation<u64, RoaringTreemap>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = RoaringObserver::default();
+        let g = o.notice(u64::MAX);
+        assert!(g.is_some());
+        assert!(o.notice(u64::MAX).is_none());
+        assert!(o.notice(0).is_some());
+        drop(g);
+        assert!(o.notice(u64::MAX).is_some());
+    }
+}
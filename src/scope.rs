@@ -0,0 +1,96 @@
+use crate::{Link, Observation, Observer, Recorder};
+use alloc::rc::Rc;
+
+/**
+A Scope groups the observations made through it, and forgets them all when
+it is dropped, even those whose guards are still alive elsewhere.
+
+This gives arena-style cleanup for a phase of a traversal: observations can
+be stashed in longer-lived structures without outliving the phase. Once the
+scope has swept an item up, dropping its guard does nothing.
+```
+use forgetful::Observer;
+let observer = Observer::new();
+let mut stashed = Vec::new();
+{
+    let phase = observer.scope();
+    stashed.push(phase.notice("a").unwrap());
+    stashed.push(phase.notice("b").unwrap());
+    assert!(phase.notice("a").is_none());
+}
+assert!(observer.is_empty());
+drop(stashed);
+```
+*/
+pub struct Scope<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    observer: &'o Observer<'a, T, R>,
+    link: Rc<Link<&'a T>>,
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Opens a [`Scope`], which forgets every observation made through it
+    /// when dropped.
+    pub fn scope(&self) -> Scope<'_, 'a, T, R> {
+        Scope {
+            observer: self,
+            link: Link::new(),
+        }
+    }
+}
+
+impl<'o, 'a, T, R> Scope<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Notices `item` in the scope's observer, to be forgotten when the
+    /// scope is dropped, if not before. See [`Observer::notice`].
+    #[track_caller]
+    pub fn notice(&self, item: &'a T) -> Option<Observation<'a, T, R>> {
+        Observation::child_of(&self.observer.shared, &self.link, None, item)
+    }
+
+    /// Returns the observer the scope notices items in.
+    pub fn observer(&self) -> &'o Observer<'a, T, R> {
+        self.observer
+    }
+}
+
+impl<'o, 'a, T, R> Drop for Scope<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn drop(&mut self) {
+        self.observer.shared.sweep(&self.link, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn forgets_only_what_was_noticed_through_it() {
+        let o = Observer::new();
+        let outside = o.notice(&0).unwrap();
+        let scope = o.scope();
+        let a = scope.notice(&1).unwrap();
+        let nested = a.notice_child(&2).unwrap();
+        drop(a);
+        assert_eq!(o.len(), 1);
+        let b = scope.notice(&3).unwrap();
+        drop(scope);
+        assert_eq!(o.len(), 1);
+        drop((b, nested));
+        assert!(o.is_observing(outside.item()));
+    }
+}
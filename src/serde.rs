@@ -0,0 +1,118 @@
+//! Serialization of observers, available with the `serde` feature.
+//!
+//! An observer serializes as a sequence of the items it is currently
+//! observing. Deserializing produces an observer *seeded* with those items:
+//! no observation owns them, so they are observed permanently. This lets a
+//! long-running traversal be checkpointed and resumed without revisiting
+//! items.
+//!
+//! ```
+//! use forgetful::Observer;
+//! let observer = Observer::new();
+//! let _done = observer.notice("a");
+//! let checkpoint = serde_json::to_string(&observer).unwrap();
+//!
+//! let restored: Observer<str> = serde_json::from_str(&checkpoint).unwrap();
+//! assert!(restored.notice("a").is_none());
+//! assert!(restored.notice("b").is_some());
+//! ```
+
+use crate::{Entry, Iterable, Observer, OwnedObserver, Recorder};
+use ::serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+use core::fmt;
+use core::marker::PhantomData;
+
+impl<'a, T, R> Serialize for Observer<'a, T, R>
+where
+    T: 'a + ?Sized + Serialize,
+    R: Iterable<&'a T>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.observed().iter())
+    }
+}
+
+impl<T, R> Serialize for OwnedObserver<T, R>
+where
+    T: Clone + Serialize,
+    R: Iterable<T>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.recorder.borrow().iter())
+    }
+}
+
+/// Deserializes a sequence of items straight into a recorder.
+struct Seed<K, R>(PhantomData<(K, R)>);
+
+impl<'de, K, R> Visitor<'de> for Seed<K, R>
+where
+    K: Deserialize<'de>,
+    R: Recorder<K> + Default,
+{
+    type Value = R;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of observed items")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<R, A::Error> {
+        let mut recorder = R::default();
+        while let Some(item) = seq.next_element()? {
+            recorder.insert(item, Entry::default());
+        }
+        Ok(recorder)
+    }
+}
+
+impl<'de, T, R> Deserialize<'de> for Observer<'de, T, R>
+where
+    T: 'de + ?Sized,
+    &'de T: Deserialize<'de>,
+    R: Recorder<&'de T> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_seq(Seed(PhantomData))
+            .map(Self::with_recorder)
+    }
+}
+
+impl<'de, T, R> Deserialize<'de> for OwnedObserver<T, R>
+where
+    T: Clone + Deserialize<'de>,
+    R: Recorder<T> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_seq(Seed(PhantomData))
+            .map(Self::with_recorder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Observer, OwnedObserver};
+
+    #[test]
+    fn serializes_current_observations() {
+        let o = Observer::with_recorder(alloc::collections::BTreeSet::new());
+        let _g1 = o.notice(&1);
+        let g2 = o.notice(&2);
+        let _g3 = o.notice(&3);
+        drop(g2);
+        assert_eq!(serde_json::to_string(&o).unwrap(), "[1,3]");
+    }
+
+    #[test]
+    fn restored_items_are_observed_permanently() {
+        let o: OwnedObserver<String> = serde_json::from_str(r#"["a","b"]"#).unwrap();
+        assert_eq!(o.len(), 2);
+        assert!(o.notice("a".to_string()).is_none());
+        let g = o.notice("c".to_string());
+        assert!(g.is_some());
+        drop(g);
+        assert_eq!(o.len(), 2);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{SyncObservation, SyncObserver};
+use std::cmp::Eq;
+use std::collections::hash_map::RandomState;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/**
+ShardedObserver spreads items over `N` independent [`SyncObserver`]s, each
+with its own lock.
+
+An item always hashes to the same shard, so deduplication is exact; only
+notices of items that land in the same shard contend with each other.
+```
+use forgetful::ShardedObserver;
+let observer = ShardedObserver::<_, 16>::new();
+{
+    let observation = observer.notice("foo").expect("never seen before");
+    assert!(observer.notice("foo").is_none());
+}
+assert!(observer.notice("foo").is_some());
+```
+*/
+pub struct ShardedObserver<'a, T, const N: usize>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    hasher: RandomState,
+    shards: [SyncObserver<'a, T>; N],
+}
+
+impl<'a, T, const N: usize> Default for ShardedObserver<'a, T, N>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const N: usize> Debug for ShardedObserver<'a, T, N>
+where
+    T: 'a + Eq + Hash + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.shards.iter()).finish()
+    }
+}
+
+impl<'a, T, const N: usize> ShardedObserver<'a, T, N>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Creates an observer with `N` shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new() -> Self {
+        assert!(N > 0, "ShardedObserver needs at least one shard");
+        Self {
+            hasher: RandomState::new(),
+            shards: [(); N].map(|_| SyncObserver::new()),
+        }
+    }
+
+    pub fn notice(&self, item: &'a T) -> Option<SyncObservation<'a, T>> {
+        self.shard(item).notice(item)
+    }
+
+    fn shard(&self, item: &T) -> &SyncObserver<'a, T> {
+        let mut hasher = self.hasher.build_hasher();
+        item.hash(&mut hasher);
+        &self.shards[(hasher.finish() % N as u64) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = ShardedObserver::<_, 4>::new();
+        let g = o.notice(&1);
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[test]
+    fn items_in_different_shards_are_independent() {
+        let o = ShardedObserver::<_, 8>::new();
+        let items: Vec<u32> = (0..100).collect();
+        let guards: Vec<_> = items.iter().map(|i| o.notice(i)).collect();
+        assert!(guards.iter().all(Option::is_some));
+        assert!(items.iter().all(|i| o.notice(i).is_none()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_shards_panics() {
+        ShardedObserver::<u32, 0>::new();
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{Observer, Recorder};
+
+/**
+Stats are the counters an [`Observer`] keeps, as returned by
+[`Observer::stats`].
+```
+use forgetful::Observer;
+let observer = Observer::new();
+let _a = observer.notice("a");
+let _again = observer.notice("a");
+drop(observer.notice("b"));
+
+let stats = observer.stats();
+assert_eq!((stats.noticed(), stats.duplicates()), (2, 1));
+assert_eq!((stats.active(), stats.peak()), (1, 2));
+```
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    noticed: u64,
+    duplicates: u64,
+    active: usize,
+    peak: usize,
+}
+
+impl Stats {
+    /// Returns the number of items noticed, i.e. the number of observations
+    /// made, not counting clones.
+    pub fn noticed(&self) -> u64 {
+        self.noticed
+    }
+
+    /// Returns the number of times an item was rejected because it was
+    /// already observed.
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
+    /// Returns the number of observations alive, as with
+    /// [`Observer::active_count`].
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Returns the most observations that have been alive at once, as with
+    /// [`Observer::peak_active`].
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// Returns the share of attempts to notice an item that were rejected as
+    /// duplicates, or None if there were none.
+    pub fn duplicate_ratio(&self) -> Option<f64> {
+        let attempts = self.noticed + self.duplicates;
+        (attempts > 0).then(|| self.duplicates as f64 / attempts as f64)
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Returns the observer's counters: how many items it has noticed and
+    /// rejected as duplicates, and how many observations are and have at
+    /// most been alive.
+    ///
+    /// The counters aren't reset by [`forget_all`](Self::forget_all).
+    pub fn stats(&self) -> Stats {
+        Stats {
+            noticed: self.shared.noticed.get(),
+            duplicates: self.shared.duplicates.get(),
+            active: self.shared.active.get(),
+            peak: self.shared.peak.get(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn counts_notices_and_duplicates() {
+        let o = Observer::new();
+        assert_eq!(o.stats().duplicate_ratio(), None);
+        let g = o.notice(&1).unwrap();
+        let _child = g.notice_child(&2).unwrap();
+        assert!(g.notice_child(&1).is_none());
+        let (_claimed, rejected) = o.notice_any([&2, &3]);
+        assert_eq!(rejected, [&2]);
+        let stats = o.stats();
+        assert_eq!((stats.noticed(), stats.duplicates()), (3, 2));
+        assert_eq!(stats.duplicate_ratio(), Some(0.4));
+        o.forget_all();
+        assert_eq!(o.stats().noticed(), 3);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{OwnedSyncObservation, OwnedSyncObserver};
+use core::cmp::Eq;
+use core::hash::Hash;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::Stream;
+
+/// Extends [`Stream`]s with [`scoped_unique`](Self::scoped_unique). It is
+/// available with the `futures` feature.
+pub trait StreamExt: Stream {
+    /**
+    Filters out items that are currently observed by `observer`, and yields
+    the rest together with their observation.
+
+    Unlike a permanent dedup, an item is only suppressed while the first copy
+    is still being handled: once its observation is dropped, the next copy to
+    arrive gets through.
+    ```
+    use forgetful::{OwnedSyncObserver, StreamExt as _};
+    use futures::stream::{self, StreamExt};
+
+    # futures::executor::block_on(async {
+    let observer = OwnedSyncObserver::new();
+    let mut events = stream::iter(["a", "b", "a", "b"]).scoped_unique(&observer);
+    let (a, handling_a) = events.next().await.unwrap();
+    let (b, handling_b) = events.next().await.unwrap();
+    drop(handling_b);
+    // The second "a" is skipped while the first one is handled.
+    let (again, _) = events.next().await.unwrap();
+    assert_eq!((a, b, again), ("a", "b", "b"));
+    # drop(handling_a);
+    # });
+    ```
+    */
+    fn scoped_unique<T>(self, observer: &OwnedSyncObserver<T>) -> ScopedUnique<'_, Self, T>
+    where
+        Self: Sized + Stream<Item = T>,
+        T: Eq + Hash + Clone,
+    {
+        ScopedUnique {
+            stream: self,
+            observer,
+        }
+    }
+}
+
+impl<S> StreamExt for S where S: Stream {}
+
+pin_project_lite::pin_project! {
+    /// The stream returned by [`StreamExt::scoped_unique`].
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ScopedUnique<'o, S, T>
+    where
+        T: Eq,
+        T: Hash,
+        T: Clone,
+    {
+        #[pin]
+        stream: S,
+        observer: &'o OwnedSyncObserver<T>,
+    }
+}
+
+impl<'o, S, T> Stream for ScopedUnique<'o, S, T>
+where
+    S: Stream<Item = T>,
+    T: Eq + Hash + Clone,
+{
+    type Item = (T, OwnedSyncObservation<T>);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if let Some(observation) = this.observer.notice(item.clone()) {
+                        return Poll::Ready(Some((item, observation)));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.stream.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamExt as _;
+    use crate::OwnedSyncObserver;
+    use futures::stream::{self, StreamExt};
+
+    #[tokio::test]
+    async fn skips_only_items_still_observed() {
+        let o = OwnedSyncObserver::new();
+        let seen: Vec<_> = stream::iter([1, 1, 2, 1])
+            .scoped_unique(&o)
+            .map(|(item, _observation)| item)
+            .collect()
+            .await;
+        // Each observation is dropped as soon as `map` returns.
+        assert_eq!(seen, [1, 1, 2, 1]);
+
+        let kept: Vec<_> = stream::iter([1, 1, 2, 1]).scoped_unique(&o).collect().await;
+        assert_eq!(kept.len(), 2);
+        assert!(o.is_observing(&1) && o.is_observing(&2));
+    }
+}
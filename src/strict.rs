@@ -0,0 +1,71 @@
+use crate::{DuplicateError, Entry, Observer, Recorder};
+use core::fmt::Debug;
+use core::panic::Location;
+
+/// Panics about a duplicate of `item`, noticed at `location`.
+pub(crate) type Panic<K> = fn(&K, Option<Entry>, &'static Location<'static>);
+
+fn panic_on_duplicate<T>(item: &&T, entry: Option<Entry>, location: &'static Location<'static>)
+where
+    T: ?Sized + Debug,
+{
+    panic!(
+        "{}; noticed again at {}",
+        DuplicateError::new(*item, entry),
+        location
+    )
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized + Debug,
+    R: Recorder<&'a T>,
+{
+    /// Makes the observer strict: noticing an item that is already observed
+    /// panics, rather than being reported, with a message saying where the
+    /// duplicate was noticed and, as with [`DuplicateError`], what is known
+    /// about the observation holding the item. This goes for every way of
+    /// noticing items, including [`try_notice`](Self::try_notice).
+    ///
+    /// Use it in tests and debug builds, where a duplicate should be a loud
+    /// failure:
+    ///
+    /// ```should_panic
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let observer = if cfg!(debug_assertions) { observer.strict() } else { observer };
+    /// let _db = observer.notice("db");
+    /// observer.notice("db"); // panics: "db" is already observed; noticed again at ...
+    /// ```
+    pub fn strict(self) -> Self {
+        self.shared.strict.set(Some(panic_on_duplicate::<T>));
+        self
+    }
+
+    /// Returns true if the observer is [strict](Self::strict).
+    pub fn is_strict(&self) -> bool {
+        self.shared.strict.get().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+    use alloc::{format, string::String};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[test]
+    fn panics_on_duplicates() {
+        let o = Observer::new().strict();
+        assert!(o.is_strict() && !Observer::<i32>::new().is_strict());
+        let _a = o.notice(&1).unwrap();
+        let line = line!() + 1;
+        let err = catch_unwind(AssertUnwindSafe(|| o.notice(&1))).unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("1 is already observed"), "{}", message);
+        let again = format!("noticed again at src/strict.rs:{}:", line);
+        assert!(message.contains(&again), "{}", message);
+        assert_eq!(o.stats().duplicates(), 1);
+        assert!(o.notice(&2).is_some());
+    }
+}
@@ -0,0 +1,769 @@
+use crate::Timeout;
+use std::borrow::Borrow;
+use std::cmp::Eq;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// The state a thread-safe observer shares with its observations.
+struct Shared<S> {
+    items: Mutex<S>,
+    /// Notified whenever an item is forgotten.
+    released: Condvar,
+}
+
+impl<S: Default> Default for Shared<S> {
+    fn default() -> Self {
+        Self {
+            items: Mutex::default(),
+            released: Condvar::new(),
+        }
+    }
+}
+
+impl<S> Shared<S> {
+    /// Blocks until `insert` succeeds on the items, or until `deadline`
+    /// passes, in which case it returns false.
+    fn insert_until(
+        &self,
+        deadline: Option<Instant>,
+        mut insert: impl FnMut(&mut S) -> bool,
+    ) -> bool {
+        let mut items = lock(&self.items);
+        while !insert(&mut items) {
+            items = match deadline {
+                None => self
+                    .released
+                    .wait(items)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return false;
+                    }
+                    self.released
+                        .wait_timeout(items, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        }
+        true
+    }
+
+    /// Forgets an item with `remove`, and wakes any threads waiting for it.
+    fn release(&self, remove: impl FnOnce(&mut S) -> bool) {
+        if remove(&mut lock(&self.items)) {
+            self.released.notify_all();
+        }
+    }
+}
+
+type Recorder<'a, T> = Arc<Shared<HashSet<&'a T>>>;
+
+// The recorders hold nothing but items, so a panic while the lock is held
+// can't leave them in an inconsistent state. Recover from poisoning rather
+// than propagating panics into unrelated threads (or into Drop).
+pub(crate) fn lock<S>(recorder: &Mutex<S>) -> MutexGuard<'_, S> {
+    recorder.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/**
+A thread-safe counterpart to [`Observation`](crate::Observation), produced
+by [`SyncObserver::notice`].
+
+It shares its observer's state through an `Arc`, so it is `Send` and
+doesn't borrow the observer. Within [`std::thread::scope`], an observation of
+borrowed data can be handed to a worker thread, which finishes the work and
+releases the item by dropping it.
+```
+use forgetful::SyncObserver;
+use std::thread;
+
+let jobs = vec!["build".to_string(), "test".to_string()];
+let observer = SyncObserver::new();
+thread::scope(|scope| {
+    for job in &jobs {
+        let claimed = observer.notice(job).expect("not claimed yet");
+        scope.spawn(move || {
+            assert!(claimed.item().len() > 0);
+            // `job` is released here, on the worker.
+        });
+    }
+});
+assert_eq!(observer.len(), 0);
+```
+*/
+pub struct SyncObservation<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    item: &'a T,
+    recorder: Recorder<'a, T>,
+}
+
+impl<'a, T> Debug for SyncObservation<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.item)
+    }
+}
+
+impl<'a, T> SyncObservation<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Returns the observed item.
+    pub fn item(&self) -> &'a T {
+        self.item
+    }
+}
+
+impl<'a, T> Drop for SyncObservation<'a, T>
+where
+    T: Eq + Hash + ?Sized,
+{
+    fn drop(&mut self) {
+        self.recorder.release(|items| items.remove(self.item));
+    }
+}
+
+/**
+SyncObserver is a thread-safe variant of [`Observer`](crate::Observer).
+
+It can be shared between threads (for example, behind an `Arc` or
+by reference within [`std::thread::scope`]), and the observations it hands
+out may be sent to other threads and dropped there.
+```
+use forgetful::SyncObserver;
+let observer = SyncObserver::new();
+{
+    let observation = observer.notice("foo").expect("never seen before");
+    assert!(observer.notice("foo").is_none());
+}
+assert!(observer.notice("foo").is_some());
+```
+*/
+pub struct SyncObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    recorder: Recorder<'a, T>,
+}
+
+impl<'a, T> Default for SyncObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> Debug for SyncObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        lock(&self.recorder.items).fmt(f)
+    }
+}
+
+impl<'a, T> SyncObserver<'a, T>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    pub fn new() -> Self {
+        Self {
+            recorder: Default::default(),
+        }
+    }
+
+    pub fn notice(&self, item: &'a T) -> Option<SyncObservation<'a, T>> {
+        // Check and insert under a single lock, so two threads can't both
+        // observe the same item.
+        if lock(&self.recorder.items).insert(item) {
+            Some(SyncObservation {
+                item,
+                recorder: Arc::clone(&self.recorder),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Observes `item`, first blocking the thread until any existing
+    /// observation of it is dropped.
+    ///
+    /// Waiting isn't fair: whenever any item is released, every waiting
+    /// thread wakes and tries again, in no particular order, so the first to
+    /// take the lock gets the item and the rest go back to waiting. A thread
+    /// may thus be overtaken by later ones, even by a plain
+    /// [`notice`](Self::notice). Where waiters must be served in order, use
+    /// an [`AsyncObserver`](crate::AsyncObserver), which hands an item to
+    /// the one that has waited longest.
+    ///
+    /// ```
+    /// use forgetful::SyncObserver;
+    /// let observer = SyncObserver::new();
+    /// let first = observer.notice("job").unwrap();
+    /// std::thread::scope(|scope| {
+    ///     let waiter = scope.spawn(|| observer.notice_blocking("job"));
+    ///     drop(first);
+    ///     let _second = waiter.join().unwrap();
+    ///     assert!(observer.notice("job").is_none());
+    /// });
+    /// ```
+    pub fn notice_blocking(&self, item: &'a T) -> SyncObservation<'a, T> {
+        self.recorder.insert_until(None, |items| items.insert(item));
+        SyncObservation {
+            item,
+            recorder: Arc::clone(&self.recorder),
+        }
+    }
+
+    /// Like [`notice_blocking`](Self::notice_blocking), but gives up with a
+    /// [`Timeout`] if the item isn't released within `timeout`.
+    ///
+    /// ```
+    /// use forgetful::{SyncObserver, Timeout};
+    /// use std::time::Duration;
+    /// let observer = SyncObserver::new();
+    /// let _held = observer.notice("job").unwrap();
+    /// let waited = observer.notice_timeout("job", Duration::from_millis(10));
+    /// assert_eq!(waited.unwrap_err(), Timeout);
+    /// ```
+    pub fn notice_timeout(
+        &self,
+        item: &'a T,
+        timeout: Duration,
+    ) -> Result<SyncObservation<'a, T>, Timeout> {
+        self.notice_until(Instant::now().checked_add(timeout), item)
+    }
+
+    /// Like [`notice_timeout`](Self::notice_timeout), but gives up at
+    /// `deadline`.
+    pub fn notice_deadline(
+        &self,
+        item: &'a T,
+        deadline: Instant,
+    ) -> Result<SyncObservation<'a, T>, Timeout> {
+        self.notice_until(Some(deadline), item)
+    }
+
+    fn notice_until(
+        &self,
+        deadline: Option<Instant>,
+        item: &'a T,
+    ) -> Result<SyncObservation<'a, T>, Timeout> {
+        if self
+            .recorder
+            .insert_until(deadline, |items| items.insert(item))
+        {
+            Ok(SyncObservation {
+                item,
+                recorder: Arc::clone(&self.recorder),
+            })
+        } else {
+            Err(Timeout)
+        }
+    }
+
+    /// Observes all of `items` at once, or none of them if any is already
+    /// observed. See [`Observer::notice_all`](crate::Observer::notice_all).
+    ///
+    /// The items are checked and inserted under a single lock, so no other
+    /// thread sees part of the batch, and two threads claiming overlapping
+    /// batches can't each end up holding some items while waiting on the
+    /// others.
+    ///
+    /// ```
+    /// use forgetful::SyncObserver;
+    /// let observer = SyncObserver::new();
+    /// let _b = observer.notice("b");
+    /// assert!(observer.notice_all(["a", "b", "c"]).is_none());
+    /// assert!(!observer.is_observing("a"));
+    /// let claimed = observer.notice_all(["a", "c"]).unwrap();
+    /// assert_eq!(claimed.len(), 2);
+    /// ```
+    pub fn notice_all<I>(&self, items: I) -> Option<Vec<SyncObservation<'a, T>>>
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        let items: Vec<&'a T> = items.into_iter().collect();
+        let mut recorded = lock(&self.recorder.items);
+        if let Some(duplicate) = items.iter().position(|item| !recorded.insert(item)) {
+            for item in &items[..duplicate] {
+                recorded.remove(item);
+            }
+            return None;
+        }
+        drop(recorded);
+        Some(
+            items
+                .into_iter()
+                .map(|item| SyncObservation {
+                    item,
+                    recorder: Arc::clone(&self.recorder),
+                })
+                .collect(),
+        )
+    }
+
+    /// Observes whichever of `items` aren't already observed, in one pass
+    /// under a single lock, returning their observations along with the
+    /// items that were duplicates. See
+    /// [`Observer::notice_any`](crate::Observer::notice_any).
+    ///
+    /// ```
+    /// use forgetful::SyncObserver;
+    /// let observer = SyncObserver::new();
+    /// let _b = observer.notice("b");
+    /// let (claimed, duplicates) = observer.notice_any(["a", "b", "c"]);
+    /// assert_eq!(claimed.len(), 2);
+    /// assert_eq!(duplicates, ["b"]);
+    /// ```
+    pub fn notice_any<I>(&self, items: I) -> (Vec<SyncObservation<'a, T>>, Vec<&'a T>)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        let (claimed, duplicates): (Vec<_>, Vec<_>) = {
+            let mut recorded = lock(&self.recorder.items);
+            items.into_iter().partition(|item| recorded.insert(item))
+        };
+        let claimed = claimed
+            .into_iter()
+            .map(|item| SyncObservation {
+                item,
+                recorder: Arc::clone(&self.recorder),
+            })
+            .collect();
+        (claimed, duplicates)
+    }
+
+    /// Returns true if `item` is currently observed.
+    pub fn is_observing(&self, item: &T) -> bool {
+        lock(&self.recorder.items).contains(item)
+    }
+
+    /// Returns the number of items currently observed.
+    pub fn len(&self) -> usize {
+        lock(&self.recorder.items).len()
+    }
+
+    /// Returns true if no items are currently observed.
+    pub fn is_empty(&self) -> bool {
+        lock(&self.recorder.items).is_empty()
+    }
+}
+
+/// A thread-safe counterpart to [`OwnedObservation`](crate::OwnedObservation),
+/// produced by [`OwnedSyncObserver::notice`].
+pub struct OwnedSyncObservation<T>
+where
+    T: Eq + Hash + Clone,
+{
+    item: T,
+    recorder: Arc<Shared<HashSet<T>>>,
+}
+
+impl<T> Debug for OwnedSyncObservation<T>
+where
+    T: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.item)
+    }
+}
+
+impl<T> OwnedSyncObservation<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// Returns the observed item.
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+}
+
+impl<T> Drop for OwnedSyncObservation<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn drop(&mut self) {
+        self.recorder.release(|items| items.remove(&self.item));
+    }
+}
+
+/**
+OwnedSyncObserver is a thread-safe variant of
+[`OwnedObserver`](crate::OwnedObserver): it stores its items by value, so it
+has no lifetime and can be kept in a `static` or moved into spawned threads.
+```
+use forgetful::OwnedSyncObserver;
+use std::sync::Arc;
+let observer = Arc::new(OwnedSyncObserver::new());
+let observation = observer.notice("foo".to_string()).expect("never seen before");
+let other = Arc::clone(&observer);
+std::thread::spawn(move || assert!(other.notice("foo".to_string()).is_none()))
+    .join()
+    .unwrap();
+```
+*/
+pub struct OwnedSyncObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    recorder: Arc<Shared<HashSet<T>>>,
+}
+
+impl<T> Default for OwnedSyncObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for OwnedSyncObserver<T>
+where
+    T: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        lock(&self.recorder.items).fmt(f)
+    }
+}
+
+impl<T> OwnedSyncObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            recorder: Default::default(),
+        }
+    }
+
+    pub fn notice(&self, item: T) -> Option<OwnedSyncObservation<T>> {
+        let mut items = lock(&self.recorder.items);
+        // Check first, so duplicates don't pay for a clone.
+        if items.contains(&item) {
+            None
+        } else {
+            items.insert(item.clone());
+            Some(OwnedSyncObservation {
+                item,
+                recorder: Arc::clone(&self.recorder),
+            })
+        }
+    }
+
+    /// Observes `item`, first blocking the thread until any existing
+    /// observation of it is dropped. See [`SyncObserver::notice_blocking`].
+    pub fn notice_blocking(&self, item: T) -> OwnedSyncObservation<T> {
+        match self.notice_until(None, item) {
+            Ok(observation) => observation,
+            Err(Timeout) => unreachable!("waited without a deadline"),
+        }
+    }
+
+    /// Like [`notice_blocking`](Self::notice_blocking), but gives up with a
+    /// [`Timeout`] if the item isn't released within `timeout`.
+    pub fn notice_timeout(
+        &self,
+        item: T,
+        timeout: Duration,
+    ) -> Result<OwnedSyncObservation<T>, Timeout> {
+        self.notice_until(Instant::now().checked_add(timeout), item)
+    }
+
+    /// Like [`notice_timeout`](Self::notice_timeout), but gives up at
+    /// `deadline`.
+    pub fn notice_deadline(
+        &self,
+        item: T,
+        deadline: Instant,
+    ) -> Result<OwnedSyncObservation<T>, Timeout> {
+        self.notice_until(Some(deadline), item)
+    }
+
+    fn notice_until(
+        &self,
+        deadline: Option<Instant>,
+        item: T,
+    ) -> Result<OwnedSyncObservation<T>, Timeout> {
+        let inserted = self.recorder.insert_until(deadline, |items| {
+            // Check first, so waking up to a duplicate doesn't pay for a clone.
+            !items.contains(&item) && items.insert(item.clone())
+        });
+        if inserted {
+            Ok(OwnedSyncObservation {
+                item,
+                recorder: Arc::clone(&self.recorder),
+            })
+        } else {
+            Err(Timeout)
+        }
+    }
+
+    /// Observes an owned copy of `item`, which may be any borrowed form of
+    /// the item type (a `&str` for `String` items, say). The copy is only
+    /// made if the item isn't already observed.
+    pub fn notice_ref<Q>(&self, item: &Q) -> Option<OwnedSyncObservation<T>>
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = T> + ?Sized,
+    {
+        let mut items = lock(&self.recorder.items);
+        if items.contains(item) {
+            None
+        } else {
+            let item = item.to_owned();
+            items.insert(item.clone());
+            Some(OwnedSyncObservation {
+                item,
+                recorder: Arc::clone(&self.recorder),
+            })
+        }
+    }
+
+    /// Observes all of `items` at once, or none of them if any is already
+    /// observed, under a single lock. See [`SyncObserver::notice_all`].
+    pub fn notice_all<I>(&self, items: I) -> Option<Vec<OwnedSyncObservation<T>>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let items: Vec<T> = items.into_iter().collect();
+        let mut recorded = lock(&self.recorder.items);
+        // Check first, so duplicates don't pay for a clone.
+        if let Some(duplicate) = items
+            .iter()
+            .position(|item| recorded.contains(item) || !recorded.insert(item.clone()))
+        {
+            for item in &items[..duplicate] {
+                recorded.remove(item);
+            }
+            return None;
+        }
+        drop(recorded);
+        Some(
+            items
+                .into_iter()
+                .map(|item| OwnedSyncObservation {
+                    item,
+                    recorder: Arc::clone(&self.recorder),
+                })
+                .collect(),
+        )
+    }
+
+    /// Observes whichever of `items` aren't already observed, under a
+    /// single lock, returning their observations along with the items that
+    /// were duplicates. See [`SyncObserver::notice_any`].
+    pub fn notice_any<I>(&self, items: I) -> (Vec<OwnedSyncObservation<T>>, Vec<T>)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let (claimed, duplicates): (Vec<_>, Vec<_>) = {
+            let mut recorded = lock(&self.recorder.items);
+            // Check first, so duplicates don't pay for a clone.
+            items
+                .into_iter()
+                .partition(|item| !recorded.contains(item) && recorded.insert(item.clone()))
+        };
+        let claimed = claimed
+            .into_iter()
+            .map(|item| OwnedSyncObservation {
+                item,
+                recorder: Arc::clone(&self.recorder),
+            })
+            .collect();
+        (claimed, duplicates)
+    }
+
+    /// Returns true if `item` is currently observed. Like
+    /// [`HashSet::contains`], `item` may be any borrowed form of the item
+    /// type.
+    pub fn is_observing<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        lock(&self.recorder.items).contains(item)
+    }
+
+    /// Returns the number of items currently observed.
+    pub fn len(&self) -> usize {
+        lock(&self.recorder.items).len()
+    }
+
+    /// Returns true if no items are currently observed.
+    pub fn is_empty(&self) -> bool {
+        lock(&self.recorder.items).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn observer_and_observation_are_send_and_sync() {
+        assert_send_sync::<SyncObserver<'static, str>>();
+        assert_send_sync::<SyncObservation<'static, str>>();
+    }
+
+    #[test]
+    fn does_not_forget_if_observer_in_scope() {
+        let o = SyncObserver::new();
+        let g = o.notice(&1);
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[test]
+    fn only_one_thread_observes_an_item() {
+        let o = Arc::new(SyncObserver::<'static, str>::new());
+        let guard = o.notice("foo").unwrap();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let o = Arc::clone(&o);
+                thread::spawn(move || o.notice("foo").is_none())
+            })
+            .collect();
+        for h in handles {
+            assert!(h.join().unwrap());
+        }
+        drop(guard);
+        assert!(o.notice("foo").is_some());
+    }
+
+    #[test]
+    fn observation_can_be_dropped_on_another_thread() {
+        let o = SyncObserver::<'static, str>::new();
+        let guard = o.notice("foo").unwrap();
+        thread::spawn(move || drop(guard)).join().unwrap();
+        assert!(o.notice("foo").is_some());
+    }
+
+    #[test]
+    fn observations_of_borrowed_items_move_into_scoped_threads() {
+        let items: Vec<String> = (0..8).map(|n| n.to_string()).collect();
+        let o = SyncObserver::new();
+        thread::scope(|scope| {
+            for item in &items {
+                let observation = o.notice(item).unwrap();
+                let o = &o;
+                scope.spawn(move || {
+                    assert!(o.notice(observation.item()).is_none());
+                    drop(observation);
+                    assert!(o.notice(item).is_some());
+                });
+            }
+        });
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn notices_all_or_nothing() {
+        let o = SyncObserver::new();
+        let _held = o.notice(&2).unwrap();
+        assert!(o.notice_all([&1, &2, &3]).is_none());
+        assert_eq!(o.len(), 1);
+        let all = o.notice_all([&1, &3]).unwrap();
+        assert!(o.is_observing(&1) && o.is_observing(&3));
+        drop(all);
+        assert_eq!(o.len(), 1);
+
+        let owned = OwnedSyncObserver::new();
+        let _held = owned.notice(2).unwrap();
+        assert!(owned.notice_all([1, 2]).is_none());
+        assert!(owned.notice_all([1, 1]).is_none());
+        assert_eq!(owned.len(), 1);
+        assert_eq!(owned.notice_all([1, 3]).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn notice_any_claims_what_it_can() {
+        let o = SyncObserver::new();
+        let _held = o.notice(&2).unwrap();
+        let (claimed, duplicates) = o.notice_any([&1, &2, &3, &1]);
+        assert_eq!(
+            claimed
+                .iter()
+                .map(SyncObservation::item)
+                .collect::<Vec<_>>(),
+            [&1, &3]
+        );
+        assert_eq!(duplicates, [&2, &1]);
+        drop(claimed);
+        assert_eq!(o.len(), 1);
+
+        let owned = OwnedSyncObserver::new();
+        let _held = owned.notice(2).unwrap();
+        let (claimed, duplicates) = owned.notice_any([1, 2]);
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(duplicates, [2]);
+    }
+
+    #[test]
+    fn owned_observer_is_static() {
+        fn assert_static<T: 'static + Send + Sync>(_: &T) {}
+        let o = OwnedSyncObserver::new();
+        let g = o.notice(format!("{}", 1)).unwrap();
+        assert_static(&o);
+        assert_static(&g);
+        assert!(o.is_observing(&"1".to_string()));
+        thread::spawn(move || drop(g)).join().unwrap();
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn owned_observer_looks_up_by_str() {
+        let o: OwnedSyncObserver<String> = OwnedSyncObserver::new();
+        let _g = o.notice_ref("a").unwrap();
+        assert!(o.is_observing("a"));
+        assert!(o.notice_ref("a").is_none());
+        assert!(o.notice("a".to_string()).is_none());
+    }
+
+    #[test]
+    fn notice_blocking_waits_for_release() {
+        let o = OwnedSyncObserver::new();
+        let g = o.notice(1).unwrap();
+        thread::scope(|scope| {
+            // Each waiter gets the item in turn, once the previous one lets go.
+            let waiters: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| drop(o.notice_blocking(1))))
+                .collect();
+            drop(g);
+            for waiter in waiters {
+                waiter.join().unwrap();
+            }
+        });
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn notice_deadline_gives_up() {
+        let o = OwnedSyncObserver::new();
+        let g = o.notice(1).unwrap();
+        let deadline = Instant::now() + Duration::from_millis(20);
+        assert_eq!(o.notice_deadline(1, deadline).unwrap_err(), Timeout);
+        assert!(Instant::now() >= deadline);
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| o.notice_timeout(1, Duration::from_secs(60)).is_ok());
+            drop(g);
+            assert!(waiter.join().unwrap());
+        });
+    }
+}
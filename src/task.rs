@@ -0,0 +1,141 @@
+//! Task-local observers, available with the `tokio` feature.
+
+use crate::{OwnedSyncObservation, OwnedSyncObserver};
+use core::borrow::Borrow;
+use core::cmp::Eq;
+use core::future::Future;
+use core::hash::Hash;
+use tokio::task::LocalKey;
+
+#[doc(hidden)]
+pub use ::tokio as __tokio;
+
+/**
+TaskLocalObserver is a handle to a tokio task-local [`OwnedSyncObserver`],
+declared with [`task_local_observer!`](crate::task_local_observer).
+
+It is the async counterpart to
+[`LocalKeyObserver`](crate::LocalKeyObserver): async recursion can notice
+items without passing an observer along. Each future run through
+[`scope`](Self::scope) gets its own observer.
+```
+use forgetful::task_local_observer;
+use std::future::Future;
+use std::pin::Pin;
+
+task_local_observer! {
+    static RESOLVING: String;
+}
+
+fn resolve(name: String) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> {
+    Box::pin(async move {
+        let _resolving = RESOLVING.notice(name.clone()).ok_or(name.clone())?;
+        tokio::task::yield_now().await;
+        if name == "loop" {
+            resolve(name).await?;
+        }
+        Ok(())
+    })
+}
+
+# tokio::runtime::Runtime::new().unwrap().block_on(async {
+let task = tokio::spawn(RESOLVING.scope(resolve("loop".to_string())));
+assert_eq!(task.await.unwrap(), Err("loop".to_string()));
+# });
+```
+*/
+pub struct TaskLocalObserver<T>
+where
+    T: 'static + Eq + Hash + Clone,
+{
+    key: &'static LocalKey<OwnedSyncObserver<T>>,
+}
+
+impl<T> TaskLocalObserver<T>
+where
+    T: 'static + Eq + Hash + Clone,
+{
+    #[doc(hidden)]
+    pub const fn new(key: &'static LocalKey<OwnedSyncObserver<T>>) -> Self {
+        Self { key }
+    }
+
+    /// Runs `f` with a fresh observer of its own.
+    pub fn scope<F>(&'static self, f: F) -> impl Future<Output = F::Output>
+    where
+        F: Future,
+    {
+        self.key.scope(OwnedSyncObserver::new(), f)
+    }
+
+    /// Notices `item` in the current task's observer. See
+    /// [`OwnedSyncObserver::notice`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of [`scope`](Self::scope).
+    pub fn notice(&'static self, item: T) -> Option<OwnedSyncObservation<T>> {
+        self.key.with(|observer| observer.notice(item))
+    }
+
+    /// Returns true if `item` is currently observed by the current task.
+    /// `item` may be any borrowed form of the item type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of [`scope`](Self::scope).
+    pub fn is_observing<Q>(&'static self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.key.with(|observer| observer.is_observing(item))
+    }
+}
+
+/**
+Declares a static [`TaskLocalObserver`] of the given item type. Available
+with the `tokio` feature.
+```
+forgetful::task_local_observer! {
+    /// Urls being fetched by the current task.
+    pub static FETCHING: String;
+}
+```
+*/
+#[macro_export]
+macro_rules! task_local_observer {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::TaskLocalObserver<$t> = {
+            $crate::__tokio::task_local! {
+                static OBSERVER: $crate::OwnedSyncObserver<$t>;
+            }
+            $crate::TaskLocalObserver::new(&OBSERVER)
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    task_local_observer! {
+        static SEEN: u32;
+    }
+
+    #[tokio::test]
+    async fn tasks_have_separate_observers() {
+        SEEN.scope(async {
+            let _g = SEEN.notice(1).unwrap();
+            assert!(SEEN.notice(1).is_none());
+            let other = tokio::spawn(SEEN.scope(async { SEEN.notice(1).is_some() }));
+            assert!(other.await.unwrap());
+        })
+        .await;
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_outside_of_a_scope() {
+        SEEN.notice(1);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Entry, Iterable, Lookup, Observer, Recorder};
+use core::cell::RefCell;
+use core::fmt::{self, Debug};
+use std::time::Instant;
+
+/// An item as shown by the `Debug` output of observers and observations
+/// with the `timestamps` feature: followed by how long ago it was noticed,
+/// if its entry knows.
+pub(crate) struct Timed<'e, T: ?Sized>(pub(crate) &'e T, pub(crate) Option<&'e Entry>);
+
+impl<'e, T> Debug for Timed<'e, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)?;
+        match self.1.and_then(Entry::created) {
+            Some(created) => write!(f, " (noticed {:?} ago)", created.elapsed()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// With the `timestamps` feature, entries remember when their items were
+/// noticed, so an observer can tell how long its items have been observed.
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Returns when `item` was noticed, or None if it isn't observed, was
+    /// observed permanently, or the recorder doesn't keep entries.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// use std::time::Instant;
+    /// let before = Instant::now();
+    /// let observer = Observer::new();
+    /// let _job = observer.notice("job");
+    /// assert!(observer.observed_since("job").unwrap() >= before);
+    /// ```
+    pub fn observed_since(&self, item: &T) -> Option<Instant>
+    where
+        R: Lookup<&'a T, T>,
+    {
+        RefCell::borrow(&self.shared.recorder)
+            .entry_ref(item)
+            .and_then(|entry| entry.created())
+    }
+
+    /// Returns the item that has been observed the longest, with when it was
+    /// noticed, e.g. to find the request that is stuck.
+    pub fn longest_observed(&self) -> Option<(&'a T, Instant)>
+    where
+        R: Iterable<&'a T>,
+    {
+        let recorder = RefCell::borrow(&self.shared.recorder);
+        recorder
+            .iter()
+            .filter_map(|item| Some((*item, recorder.entry(item)?.created()?)))
+            .min_by_key(|(_, created)| *created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn debug_output_says_when_items_were_noticed() {
+        let o = Observer::with_blocked([&0]);
+        let a = o.notice(&1).unwrap();
+        assert!(format!("{:?}", a).starts_with("1 (noticed "));
+        assert!(format!("{:?}", a).ends_with(" ago)"));
+        let debug = format!("{:?}", o);
+        assert!(debug.contains("0, ") || debug.contains(", 0}"));
+        assert!(debug.contains("1 (noticed "));
+    }
+
+    #[test]
+    fn finds_the_oldest_observation() {
+        let o = Observer::with_blocked([&0]);
+        let _a = o.notice(&1).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let _b = o.notice(&2).unwrap();
+        assert_eq!(o.observed_since(&0), None);
+        assert!(o.observed_since(&1) < o.observed_since(&2));
+        assert_eq!(o.longest_observed().map(|(item, _)| item), Some(&1));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::hash::HashMap;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::cmp::Eq;
+use core::fmt::Debug;
+use core::hash::Hash;
+
+/// An opaque handle to an observation made by [`TokenObserver::notice`].
+///
+/// Tokens are plain `Copy` data with no lifetime, so they can be stored
+/// anywhere. Each token carries a generation: once its observation has been
+/// forgotten, the token goes stale and can't affect a later observation that
+/// happens to reuse the same slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObservationToken {
+    index: usize,
+    generation: u64,
+}
+
+struct Slot<T> {
+    generation: u64,
+    item: Option<T>,
+}
+
+struct Slots<T> {
+    index: HashMap<T, usize>,
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+}
+
+/**
+TokenObserver records observations explicitly rather than through scoped
+guards.
+
+[`notice`](Self::notice) hands out an [`ObservationToken`], and the item stays
+observed until that token is passed to [`forget`](Self::forget). This suits
+code where a guard's lifetime can't be expressed, such as long-lived
+structs, FFI layers, or hand-written state machines.
+```
+use forgetful::TokenObserver;
+let observer = TokenObserver::new();
+let token = observer.notice("foo").expect("never seen before");
+assert!(observer.notice("foo").is_none());
+assert!(observer.forget(token));
+assert!(observer.notice("foo").is_some());
+```
+*/
+pub struct TokenObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    slots: RefCell<Slots<T>>,
+}
+
+impl<T> Default for TokenObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for TokenObserver<T>
+where
+    T: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set()
+            .entries(self.slots.borrow().index.keys())
+            .finish()
+    }
+}
+
+impl<T> TokenObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            slots: RefCell::new(Slots {
+                index: HashMap::new(),
+                slots: Vec::new(),
+                free: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn notice(&self, item: T) -> Option<ObservationToken> {
+        let mut slots = self.slots.borrow_mut();
+        if slots.index.contains_key(&item) {
+            return None;
+        }
+        let index = match slots.free.pop() {
+            Some(index) => index,
+            None => {
+                slots.slots.push(Slot {
+                    generation: 0,
+                    item: None,
+                });
+                slots.slots.len() - 1
+            }
+        };
+        slots.index.insert(item.clone(), index);
+        let slot = &mut slots.slots[index];
+        slot.item = Some(item);
+        Some(ObservationToken {
+            index,
+            generation: slot.generation,
+        })
+    }
+
+    /// Forgets the observation represented by `token`.
+    ///
+    /// Returns false if the token is stale, i.e. it was already forgotten.
+    pub fn forget(&self, token: ObservationToken) -> bool {
+        let mut slots = self.slots.borrow_mut();
+        let item = match slots.slots.get_mut(token.index) {
+            Some(slot) if slot.generation == token.generation => match slot.item.take() {
+                Some(item) => {
+                    slot.generation += 1;
+                    item
+                }
+                None => return false,
+            },
+            _ => return false,
+        };
+        slots.index.remove(&item);
+        slots.free.push(token.index);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_forget_until_token_is_forgotten() {
+        let o = TokenObserver::new();
+        let t = o.notice(1).unwrap();
+        assert!(o.notice(1).is_none());
+        assert!(o.forget(t));
+        assert!(o.notice(1).is_some());
+    }
+
+    #[test]
+    fn stale_token_does_not_forget_reused_slot() {
+        let o = TokenObserver::new();
+        let stale = o.notice(1).unwrap();
+        assert!(o.forget(stale));
+        let fresh = o.notice(2).unwrap();
+        assert_ne!(stale, fresh);
+        assert!(!o.forget(stale));
+        assert!(o.notice(2).is_none());
+    }
+
+    #[test]
+    fn tokens_are_static() {
+        fn assert_static<T: 'static>(_: T) {}
+        let o = TokenObserver::new();
+        assert_static(o.notice(String::from("foo")).unwrap());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{Observation, Observer, Recorder, Shared};
+use core::fmt::Debug;
+use tracing::field::{self, Empty};
+use tracing::Span;
+
+/// Records an item in the span of its observation.
+pub(crate) type Describer<K> = fn(&Span, &K);
+
+fn describe<K: Debug>(span: &Span, item: &K) {
+    span.record("item", field::debug(item));
+}
+
+impl<K, R> Shared<K, R> {
+    /// Opens the span of a new observation of `item`: a debug-level
+    /// `observation` span, whose `item` field is recorded if
+    /// [`Observer::trace_items`] was called. Its parent is `parent`, if
+    /// given, and the current span otherwise.
+    pub(crate) fn span(&self, parent: Option<&Span>, item: &K) -> Span {
+        let span = match parent {
+            Some(parent) => tracing::debug_span!(parent: parent, "observation", item = Empty),
+            None => tracing::debug_span!("observation", item = Empty),
+        };
+        if let Some(describer) = self.describer.get() {
+            describer(&span, item);
+        }
+        span
+    }
+}
+
+/// With the `tracing` feature, every observation carries a span, so their
+/// lifetimes show up in traces.
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized + Debug,
+    R: Recorder<&'a T>,
+{
+    /// Has the observer record each item it notices, however it is noticed,
+    /// as the `item` field of the observation's [span](Observation::span).
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// observer.trace_items();
+    /// let job = observer.notice("job").expect("never seen before");
+    /// job.span().in_scope(|| {
+    ///     // Events here are recorded within the span of `"job"`.
+    /// });
+    /// ```
+    pub fn trace_items(&self) {
+        self.shared.describer.set(Some(describe::<&'a T>));
+    }
+
+    /// Like [`notice`](Self::notice), but records the item in the
+    /// observation's span even without [`trace_items`](Self::trace_items).
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// let observer = Observer::new();
+    /// let job = observer.notice_traced("job").expect("never seen before");
+    /// job.span().in_scope(|| {
+    ///     // Events here are recorded within the observation's span.
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn notice_traced(&self, item: &'a T) -> Option<Observation<'a, T, R>> {
+        let observation = self.notice(item)?;
+        observation.span.record("item", field::debug(item));
+        Some(observation)
+    }
+}
+
+impl<'a, T, R> Observation<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Returns the observation's span, which is closed when the observation
+    /// and all its [clones](Self::try_clone) are dropped.
+    ///
+    /// It is a debug-level `observation` span, opened under the current span
+    /// when the item is noticed, or under the parent's span for
+    /// [children](Self::notice_child), with the item as its `item` field if
+    /// the observer [traces items](Observer::trace_items). A
+    /// [transferred](Self::transfer) observation keeps its span.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::Debug;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Counts the spans opened, and the references to them still held, and
+    /// keeps the items recorded in them.
+    #[derive(Clone, Default)]
+    struct Spans {
+        opened: Arc<AtomicU64>,
+        held: Arc<AtomicU64>,
+        items: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Visit for Spans {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            if field.name() == "item" {
+                self.items.lock().unwrap().push(format!("{:?}", value));
+            }
+        }
+    }
+
+    impl Subscriber for Spans {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            self.held.fetch_add(1, Ordering::SeqCst);
+            Id::from_u64(self.opened.fetch_add(1, Ordering::SeqCst) + 1)
+        }
+
+        fn clone_span(&self, id: &Id) -> Id {
+            self.held.fetch_add(1, Ordering::SeqCst);
+            id.clone()
+        }
+
+        fn try_close(&self, _: Id) -> bool {
+            self.held.fetch_sub(1, Ordering::SeqCst) == 1
+        }
+
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            values.record(&mut self.clone());
+        }
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn span_lasts_as_long_as_the_observation() {
+        let spans = Spans::default();
+        tracing::subscriber::with_default(spans.clone(), || {
+            let o = Observer::new();
+            let g = o.notice_traced("job").unwrap();
+            assert_eq!(g.span().id().map(|id| id.into_u64()), Some(1));
+            assert!(o.notice_traced("job").is_none());
+            let clone = g.try_clone().unwrap();
+            drop(g);
+            assert_eq!(spans.held.load(Ordering::SeqCst), 1);
+            drop(clone);
+            assert_eq!(spans.held.load(Ordering::SeqCst), 0);
+        });
+        assert_eq!(spans.opened.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn every_observation_has_a_span() {
+        let spans = Spans::default();
+        tracing::subscriber::with_default(spans.clone(), || {
+            let (o, target) = (Observer::new(), Observer::new());
+            let g = o.notice("job").unwrap();
+            let id = g.span().id();
+            assert!(id.is_some());
+            let child = g.notice_child("step").unwrap();
+            assert!(!child.span().is_disabled());
+            assert_ne!(child.span().id(), id);
+            let scope = o.scope();
+            assert!(!scope.notice("phase").unwrap().span().is_disabled());
+            drop(scope);
+            drop(child);
+            let g = g.transfer(&target).unwrap();
+            assert_eq!(g.span().id(), id);
+            drop(g);
+            assert_eq!(spans.held.load(Ordering::SeqCst), 0);
+        });
+        assert_eq!(spans.opened.load(Ordering::SeqCst), 3);
+        assert!(spans.items.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn traced_observers_record_every_item() {
+        let spans = Spans::default();
+        tracing::subscriber::with_default(spans.clone(), || {
+            let o = Observer::new();
+            o.trace_items();
+            let g = o.notice("job").unwrap();
+            let _child = g.notice_child("step").unwrap();
+            let _phase = o.scope().notice("phase");
+            let _batch = o.notice_all(["a", "b"]).unwrap();
+            let mut t = o.begin();
+            t.notice("tentative");
+        });
+        assert_eq!(
+            *spans.items.lock().unwrap(),
+            [
+                r#""job""#,
+                r#""step""#,
+                r#""phase""#,
+                r#""a""#,
+                r#""b""#,
+                r#""tentative""#
+            ]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{ObservationSet, Observer, Recorder};
+
+/**
+A Transaction notices items tentatively: they are observed as usual while it
+is open, then either kept with [`commit`](Self::commit) or forgotten with
+[`rollback`](Self::rollback), which is also what dropping it does.
+```
+use forgetful::Observer;
+let observer = Observer::new();
+let mut visit = observer.begin();
+assert!(visit.notice("a -> b"));
+assert!(visit.notice("b -> c"));
+// A batch that turns out to be invalid is undone wholesale.
+visit.rollback();
+assert!(observer.is_empty());
+
+let mut visit = observer.begin();
+visit.notice("a -> b");
+let counted = visit.commit();
+assert!(observer.is_observing("a -> b"));
+// Leak the committed set to keep its items observed permanently.
+counted.leak();
+assert!(observer.notice("a -> b").is_none());
+```
+*/
+pub struct Transaction<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    observer: &'o Observer<'a, T, R>,
+    observations: ObservationSet<'a, T, R>,
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Begins a [`Transaction`] on this observer.
+    pub fn begin(&self) -> Transaction<'_, 'a, T, R> {
+        Transaction {
+            observer: self,
+            observations: ObservationSet::new(),
+        }
+    }
+}
+
+impl<'o, 'a, T, R> Transaction<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Notices `item` as part of the transaction, returning false if it
+    /// was already observed.
+    #[track_caller]
+    pub fn notice(&mut self, item: &'a T) -> bool {
+        match self.observer.notice(item) {
+            Some(observation) => {
+                self.observations.insert(observation);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the number of items noticed in the transaction.
+    pub fn len(&self) -> usize {
+        self.observations.len()
+    }
+
+    /// Returns true if nothing has been noticed in the transaction.
+    pub fn is_empty(&self) -> bool {
+        self.observations.is_empty()
+    }
+
+    /// Keeps everything noticed in the transaction, returning the
+    /// observations, which forget their items when dropped as usual.
+    pub fn commit(mut self) -> ObservationSet<'a, T, R> {
+        core::mem::take(&mut self.observations)
+    }
+
+    /// Forgets everything noticed in the transaction, as if it had never
+    /// been noticed; see [`Recorder::undo_insert`].
+    pub fn rollback(self) {}
+}
+
+impl<'o, 'a, T, R> Drop for Transaction<'o, 'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn drop(&mut self) {
+        self.observations.undo_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn dropping_rolls_back() {
+        let o = Observer::new();
+        let _outside = o.notice(&0).unwrap();
+        {
+            let mut t = o.begin();
+            assert!(t.notice(&1));
+            assert!(!t.notice(&0));
+            assert!(!t.notice(&1));
+            assert_eq!(t.len(), 1);
+        }
+        assert_eq!(o.len(), 1);
+        let mut t = o.begin();
+        t.notice(&1);
+        let kept = t.commit();
+        assert_eq!(o.len(), 2);
+        drop(kept);
+        assert_eq!(o.len(), 1);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{Child, Link, Observation, Observer, Recorder, Shared};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::time::Duration;
+use std::time::Instant;
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Like [`notice`](Self::notice), but the item is forgotten once `ttl`
+    /// has passed, even if the observation is still alive, so that a holder
+    /// that hangs can't keep it observed forever. The observation is then
+    /// inert: dropping it does nothing, as after [`forget_all`](Self::forget_all).
+    /// A `ttl` too long to represent never expires.
+    ///
+    /// Expired items are forgotten by [`expire_stale`](Self::expire_stale),
+    /// which [`notice`](Self::notice), [`notice_child`](Observation::notice_child),
+    /// [`is_observing`](Self::is_observing), [`len`](Self::len) and
+    /// [`is_empty`](Self::is_empty) call first. Everything else, such as
+    /// [`snapshot`](Self::snapshot), batch notices and the other
+    /// recorder-specific ways of noticing, still sees expired items until
+    /// one of those runs, so call `expire_stale` before them if that
+    /// matters.
+    ///
+    /// ```
+    /// use forgetful::Observer;
+    /// use std::time::Duration;
+    /// let observer = Observer::new();
+    /// let _hung = observer.notice_with_ttl("GET /a", Duration::from_millis(10));
+    /// assert!(observer.notice_with_ttl("GET /a", Duration::from_millis(10)).is_none());
+    /// std::thread::sleep(Duration::from_millis(20));
+    /// assert!(!observer.is_observing("GET /a"));
+    /// assert!(observer.notice_with_ttl("GET /a", Duration::from_millis(10)).is_some());
+    /// ```
+    #[track_caller]
+    pub fn notice_with_ttl(&self, item: &'a T, ttl: Duration) -> Option<Observation<'a, T, R>> {
+        let observation = self.notice(item)?;
+        let deadline = match Instant::now().checked_add(ttl) {
+            Some(deadline) => deadline,
+            None => return Some(observation),
+        };
+        let link = observation.link.get_or_init(Link::new);
+        self.shared.deadlines.borrow_mut().push((
+            deadline,
+            Child {
+                item,
+                epoch: self.shared.epoch.get(),
+                generation: observation.generation,
+                link: Rc::clone(link),
+            },
+        ));
+        Some(observation)
+    }
+
+    /// Forgets the items noticed with [`notice_with_ttl`](Self::notice_with_ttl)
+    /// whose TTL has passed, along with their children, and returns how many
+    /// there were.
+    pub fn expire_stale(&self) -> usize {
+        self.shared.expire_stale()
+    }
+}
+
+impl<K, R> Shared<K, R>
+where
+    R: Recorder<K>,
+{
+    /// See [`Observer::expire_stale`]. Observers without deadlines don't
+    /// pay to read the clock.
+    pub(crate) fn expire_stale(&self) -> usize {
+        let expired: Vec<_> = {
+            let mut deadlines = self.deadlines.borrow_mut();
+            deadlines.retain(|(_, child)| child.link.owned.get());
+            if deadlines.is_empty() {
+                return 0;
+            }
+            let now = Instant::now();
+            let (expired, live) = deadlines
+                .drain(..)
+                .partition(|(deadline, _)| *deadline <= now);
+            *deadlines = live;
+            expired
+        };
+        let count = expired.len();
+        for (_, child) in expired {
+            self.sweep_child(child, false);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+    use core::time::Duration;
+
+    #[test]
+    fn expired_observations_are_inert() {
+        let o = Observer::new();
+        let _kept = o.notice_with_ttl(&3, Duration::from_secs(60)).unwrap();
+        let hung = o.notice_with_ttl(&1, Duration::from_millis(50)).unwrap();
+        let child = hung.notice_child(&2).unwrap();
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(o.expire_stale(), 1);
+        assert!(!o.is_observing(&1) && !o.is_observing(&2));
+        assert!(o.is_observing(&3));
+        let fresh = o.notice(&1).unwrap();
+        assert!(hung.try_clone().is_none());
+        drop((hung, child));
+        assert!(o.is_observing(&1));
+        drop(fresh);
+        assert_eq!(o.expire_stale(), 0);
+    }
+
+    #[test]
+    fn lookups_and_notices_expire_stale_items() {
+        let o = Observer::new();
+        let _hung = o.notice_with_ttl(&1, Duration::ZERO).unwrap();
+        assert!(!o.is_observing(&1));
+        let _hung = o.notice_with_ttl(&2, Duration::ZERO).unwrap();
+        assert_eq!(o.len(), 0);
+        let _hung = o.notice_with_ttl(&3, Duration::ZERO).unwrap();
+        let _other = o.notice(&4).unwrap();
+        assert_eq!(o.snapshot(), [&4]);
+    }
+
+    #[test]
+    fn is_empty_and_len_agree_after_expiry() {
+        let o = Observer::new();
+        let _hung = o.notice_with_ttl(&1, Duration::ZERO).unwrap();
+        assert!(o.is_empty());
+        assert_eq!(o.len(), 0);
+    }
+
+    #[test]
+    fn forget_all_drops_deadlines() {
+        let o = Observer::new();
+        let _hung = o.notice_with_ttl(&1, Duration::from_millis(1)).unwrap();
+        o.forget_all();
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(o.expire_stale(), 0);
+    }
+
+    #[test]
+    fn huge_ttls_never_expire() {
+        let o = Observer::new();
+        let _kept = o.notice_with_ttl(&1, Duration::MAX).unwrap();
+        assert_eq!(o.expire_stale(), 0);
+        assert!(o.is_observing(&1));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, Iterable, Lookup, Observed, Observer, Recorder, Shared};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+
+/**
+A read-only handle to an [`Observer`], made by [`Observer::view`].
+
+It can answer questions about what is being observed, but can't notice
+anything, so it can be handed to logging or metrics code without giving it
+a say in what counts as a duplicate. It is cheap to clone, and stays usable
+after the observer itself is dropped.
+```
+use forgetful::Observer;
+let observer = Observer::new();
+let view = observer.view();
+let _foo = observer.notice("foo");
+assert!(view.is_observing("foo"));
+assert_eq!(view.snapshot(), ["foo"]);
+```
+*/
+pub struct ObserverView<'a, T, R = HashMap<&'a T, Entry, DefaultHashBuilder>>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    shared: Rc<Shared<&'a T, R>>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T, R> Clone for ObserverView<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shared: Rc::clone(&self.shared),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, R> Debug for ObserverView<'a, T, R>
+where
+    T: 'a + ?Sized + Debug,
+    R: Iterable<&'a T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.observed().fmt(f)
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Returns a read-only [`ObserverView`] of this observer.
+    pub fn view(&self) -> ObserverView<'a, T, R> {
+        ObserverView {
+            shared: Rc::clone(&self.shared),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, R> ObserverView<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Returns true if `item` is currently observed.
+    pub fn is_observing(&self, item: &T) -> bool
+    where
+        R: Lookup<&'a T, T>,
+    {
+        RefCell::borrow(&self.shared.recorder).contains_ref(item)
+    }
+
+    /// Returns the number of items currently observed.
+    pub fn len(&self) -> usize {
+        RefCell::borrow(&self.shared.recorder).len()
+    }
+
+    /// Returns true if no items are currently observed.
+    pub fn is_empty(&self) -> bool {
+        RefCell::borrow(&self.shared.recorder).is_empty()
+    }
+
+    /// Returns the number of observations alive. See
+    /// [`Observer::active_count`].
+    pub fn active_count(&self) -> usize {
+        self.shared.active.get()
+    }
+
+    /// Returns a view of the items currently observed, to iterate over.
+    /// See [`Observer::observed`].
+    pub fn observed(&self) -> Observed<'_, 'a, T, R> {
+        Observed::new(RefCell::borrow(&self.shared.recorder))
+    }
+
+    /// Returns the items currently observed. See [`Observer::snapshot`].
+    pub fn snapshot(&self) -> Vec<&'a T>
+    where
+        R: Iterable<&'a T>,
+    {
+        self.observed().iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn follows_the_observer() {
+        let o = Observer::new();
+        let view = o.view().clone();
+        let g = o.notice(&1);
+        assert_eq!(view.len(), 1);
+        assert_eq!(view.active_count(), 1);
+        assert_eq!(view.observed().iter().collect::<Vec<_>>(), [&1]);
+        drop(g);
+        assert!(view.is_empty() && !view.is_observing(&1));
+        assert_eq!(format!("{:?}", view), "{}");
+    }
+}
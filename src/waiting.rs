@@ -0,0 +1,547 @@
+use crate::hash::HashMap;
+use crate::sync::lock;
+use core::borrow::Borrow;
+use core::cmp::Eq;
+use core::fmt::{self, Debug};
+use core::future::Future;
+use core::hash::Hash;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "tokio")]
+use {crate::Timeout, core::time::Duration, std::time::Instant};
+
+/// A task waiting for an observed item to be released.
+struct Waiter {
+    /// The id of the [`Notice`] that registered it.
+    id: u64,
+    priority: i32,
+    waker: Waker,
+}
+
+/// The tasks waiting for one observed item to be released.
+#[derive(Default)]
+struct Slot {
+    /// Waiting notices, highest priority first, then in arrival order.
+    waiters: VecDeque<Waiter>,
+    /// The waiter the item was handed to on release, which will observe it
+    /// when next polled.
+    granted: Option<u64>,
+}
+
+/// The observed items, with the tasks waiting for each of them.
+struct Waiting<T> {
+    items: HashMap<T, Slot>,
+    next_waiter: u64,
+}
+
+impl<T> Waiting<T>
+where
+    T: Eq + Hash,
+{
+    /// Releases `item`, handing it to the first waiting notice, if there is
+    /// one, and returning that notice's waker.
+    fn release(&mut self, item: &T) -> Option<Waker> {
+        let slot = self.items.get_mut(item)?;
+        match slot.waiters.pop_front() {
+            Some(waiter) => {
+                slot.granted = Some(waiter.id);
+                Some(waiter.waker)
+            }
+            None => {
+                self.items.remove(item);
+                None
+            }
+        }
+    }
+}
+
+impl<T> Default for Waiting<T> {
+    fn default() -> Self {
+        Self {
+            items: Default::default(),
+            next_waiter: 0,
+        }
+    }
+}
+
+type State<T> = Arc<Mutex<Waiting<T>>>;
+
+/// An observation produced by [`AsyncObserver`]. Dropping it hands the item
+/// to the next task waiting to observe it.
+pub struct AsyncObservation<T>
+where
+    T: Eq + Hash + Clone,
+{
+    item: T,
+    state: State<T>,
+}
+
+impl<T> Debug for AsyncObservation<T>
+where
+    T: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.item)
+    }
+}
+
+impl<T> AsyncObservation<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// Returns the observed item.
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+}
+
+impl<T> Drop for AsyncObservation<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn drop(&mut self) {
+        let waker = lock(&self.state).release(&self.item);
+        // Wake outside the lock, as a waker may poll straight away.
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/**
+AsyncObserver is an observer for async code that, rather than reporting a
+duplicate, waits for it to be released.
+
+Awaiting [`notice`](Self::notice) on an item that is already observed parks
+the task until the existing observation is dropped, then observes the item
+itself. This makes it a keyed admission control: at most one task at a time
+works on each item. It doesn't depend on any particular runtime.
+
+Waiters on the same item are served in FIFO order: when an observation is
+dropped, the item is handed straight to the task that has waited longest,
+and a newcomer can't take it in between. This means no waiter starves, as
+long as every observation is eventually dropped. Waiters registered with
+[`notice_with_priority`](Self::notice_with_priority) are served highest
+priority first instead, and in FIFO order within a priority.
+
+Waiting is cancel-safe: dropping a pending [`Notice`], e.g. when a task is
+aborted or loses a `select!`, removes its registration without observing
+anything. If the item had already been handed to it, it passes on to the
+next waiter.
+```
+use forgetful::AsyncObserver;
+use std::sync::Arc;
+
+# tokio::runtime::Runtime::new().unwrap().block_on(async {
+let observer = Arc::new(AsyncObserver::new());
+let first = observer.notice("user:1".to_string()).await;
+let waiter = tokio::spawn({
+    let observer = Arc::clone(&observer);
+    async move { observer.notice("user:1".to_string()).await.item().clone() }
+});
+tokio::task::yield_now().await;
+assert!(!waiter.is_finished());
+drop(first);
+assert_eq!(waiter.await.unwrap(), "user:1");
+# });
+```
+*/
+pub struct AsyncObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    state: State<T>,
+}
+
+impl<T> Default for AsyncObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for AsyncObserver<T>
+where
+    T: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(lock(&self.state).items.keys())
+            .finish()
+    }
+}
+
+impl<T> AsyncObserver<T>
+where
+    T: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            state: Default::default(),
+        }
+    }
+
+    /// Observes `item`, waiting for any existing observation of it to be
+    /// dropped first.
+    pub fn notice(&self, item: T) -> Notice<'_, T> {
+        self.notice_with_priority(item, 0)
+    }
+
+    /// Like [`notice`](Self::notice), but if the item is already observed,
+    /// it is handed to the highest-priority waiter first. `notice` waits at
+    /// priority 0.
+    ///
+    /// ```
+    /// use forgetful::AsyncObserver;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let observer = Arc::new(AsyncObserver::new());
+    /// let order = Arc::new(Mutex::new(Vec::new()));
+    /// let crawling = observer.notice("https://example.com").await;
+    /// let tasks: Vec<_> = [("background", -1), ("interactive", 10)]
+    ///     .into_iter()
+    ///     .map(|(name, priority)| {
+    ///         let (observer, order) = (Arc::clone(&observer), Arc::clone(&order));
+    ///         tokio::spawn(async move {
+    ///             let _refreshing = observer
+    ///                 .notice_with_priority("https://example.com", priority)
+    ///                 .await;
+    ///             order.lock().unwrap().push(name);
+    ///         })
+    ///     })
+    ///     .collect();
+    /// tokio::task::yield_now().await;
+    /// drop(crawling);
+    /// for task in tasks {
+    ///     task.await.unwrap();
+    /// }
+    /// assert_eq!(*order.lock().unwrap(), ["interactive", "background"]);
+    /// # });
+    /// ```
+    pub fn notice_with_priority(&self, item: T, priority: i32) -> Notice<'_, T> {
+        Notice {
+            observer: self,
+            item: Some(item),
+            waiter: None,
+            priority,
+        }
+    }
+
+    /// Like [`notice`](Self::notice), but gives up with a [`Timeout`] if the
+    /// item isn't released within `timeout`. It is available with the `tokio`
+    /// feature, and must run inside a Tokio runtime.
+    ///
+    /// ```
+    /// use forgetful::{AsyncObserver, Timeout};
+    /// use std::time::Duration;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let observer = AsyncObserver::new();
+    /// let _held = observer.notice("job").await;
+    /// let waited = observer.notice_timeout("job", Duration::from_millis(10)).await;
+    /// assert_eq!(waited.unwrap_err(), Timeout);
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn notice_timeout(
+        &self,
+        item: T,
+        timeout: Duration,
+    ) -> Result<AsyncObservation<T>, Timeout> {
+        tokio::time::timeout(timeout, self.notice(item))
+            .await
+            .map_err(|_| Timeout)
+    }
+
+    /// Like [`notice_timeout`](Self::notice_timeout), but gives up at
+    /// `deadline`.
+    #[cfg(feature = "tokio")]
+    pub async fn notice_deadline(
+        &self,
+        item: T,
+        deadline: Instant,
+    ) -> Result<AsyncObservation<T>, Timeout> {
+        tokio::time::timeout_at(deadline.into(), self.notice(item))
+            .await
+            .map_err(|_| Timeout)
+    }
+
+    /// Observes `item` if it isn't already observed, without waiting.
+    pub fn notice_now(&self, item: T) -> Option<AsyncObservation<T>> {
+        let mut state = lock(&self.state);
+        if state.items.contains_key(&item) {
+            None
+        } else {
+            state.items.insert(item.clone(), Slot::default());
+            Some(AsyncObservation {
+                item,
+                state: Arc::clone(&self.state),
+            })
+        }
+    }
+
+    /// Returns true if `item` is currently observed. Like
+    /// `HashSet::contains`, `item` may be any borrowed form of the item type.
+    pub fn is_observing<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        lock(&self.state).items.contains_key(item)
+    }
+
+    /// Returns the number of items currently observed.
+    pub fn len(&self) -> usize {
+        lock(&self.state).items.len()
+    }
+
+    /// Returns true if no items are currently observed.
+    pub fn is_empty(&self) -> bool {
+        lock(&self.state).items.is_empty()
+    }
+}
+
+/// The future returned by [`AsyncObserver::notice`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Notice<'o, T>
+where
+    T: Eq + Hash + Clone,
+{
+    observer: &'o AsyncObserver<T>,
+    /// The item to observe, taken once it is.
+    item: Option<T>,
+    /// The id this future's waker is registered under, if it has waited.
+    waiter: Option<u64>,
+    priority: i32,
+}
+
+// The item is never pinned, so the future can be moved freely.
+impl<'o, T> Unpin for Notice<'o, T> where T: Eq + Hash + Clone {}
+
+impl<'o, T> Debug for Notice<'o, T>
+where
+    T: Eq + Hash + Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notice").field("item", &self.item).finish()
+    }
+}
+
+impl<'o, T> Future for Notice<'o, T>
+where
+    T: Eq + Hash + Clone,
+{
+    type Output = AsyncObservation<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut state = lock(&this.observer.state);
+        let state = &mut *state;
+        let item = this.item.as_ref().expect("Notice polled after completion");
+        match state.items.get_mut(item) {
+            Some(slot) if slot.granted.is_some() && slot.granted == this.waiter => {
+                // The item was handed to us, and stays observed.
+                slot.granted = None;
+                this.waiter = None;
+            }
+            Some(slot) => {
+                let registered = this
+                    .waiter
+                    .and_then(|id| slot.waiters.iter_mut().find(|waiter| waiter.id == id));
+                match registered {
+                    Some(waiter) => {
+                        if !waiter.waker.will_wake(cx.waker()) {
+                            waiter.waker = cx.waker().clone();
+                        }
+                    }
+                    None => {
+                        let id = state.next_waiter;
+                        state.next_waiter += 1;
+                        let priority = this.priority;
+                        let at = slot
+                            .waiters
+                            .partition_point(|waiter| waiter.priority >= priority);
+                        slot.waiters.insert(
+                            at,
+                            Waiter {
+                                id,
+                                priority,
+                                waker: cx.waker().clone(),
+                            },
+                        );
+                        this.waiter = Some(id);
+                    }
+                }
+                return Poll::Pending;
+            }
+            None => {
+                state.items.insert(item.clone(), Slot::default());
+            }
+        }
+        let item = this.item.take().unwrap();
+        Poll::Ready(AsyncObservation {
+            item,
+            state: Arc::clone(&this.observer.state),
+        })
+    }
+}
+
+impl<'o, T> Drop for Notice<'o, T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn drop(&mut self) {
+        if let (Some(item), Some(id)) = (&self.item, self.waiter) {
+            let mut state = lock(&self.observer.state);
+            let granted = state.items.get_mut(item).is_some_and(|slot| {
+                slot.waiters.retain(|waiter| waiter.id != id);
+                slot.granted == Some(id)
+            });
+            let waker = if granted { state.release(item) } else { None };
+            drop(state);
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn observer_and_observation_are_send_and_sync() {
+        assert_send_sync::<AsyncObserver<String>>();
+        assert_send_sync::<AsyncObservation<String>>();
+        assert_send_sync::<Notice<'static, String>>();
+    }
+
+    #[tokio::test]
+    async fn waits_for_release() {
+        let o = AsyncObserver::new();
+        let g = o.notice(1).await;
+        assert!(o.notice_now(1).is_none());
+        let (_, g2) = tokio::join!(async move { drop(g) }, o.notice(1));
+        assert_eq!(*g2.item(), 1);
+        assert!(o.is_observing(&1));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn one_task_at_a_time_per_item() {
+        let o = Arc::new(AsyncObserver::new());
+        let running = Arc::new(AtomicUsize::new(0));
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let (o, running) = (Arc::clone(&o), Arc::clone(&running));
+                tokio::spawn(async move {
+                    let _g = o.notice("key").await;
+                    assert_eq!(running.fetch_add(1, Ordering::SeqCst), 0);
+                    tokio::task::yield_now().await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert!(o.is_empty());
+    }
+
+    #[tokio::test]
+    async fn dropping_a_waiter_deregisters_it() {
+        let o = AsyncObserver::new();
+        let _g = o.notice(1).await;
+        tokio::select! {
+            _ = o.notice(1) => unreachable!(),
+            _ = tokio::task::yield_now() => {}
+        }
+        assert!(lock(&o.state).items[&1].waiters.is_empty());
+    }
+
+    async fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        core::future::poll_fn(|cx| Poll::Ready(Pin::new(&mut *future).poll(cx))).await
+    }
+
+    #[tokio::test]
+    async fn serves_waiters_in_order() {
+        let o = Arc::new(AsyncObserver::new());
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let g = o.notice(1).await;
+        let tasks: Vec<_> = (0..4)
+            .map(|n| {
+                let (o, order) = (Arc::clone(&o), Arc::clone(&order));
+                tokio::spawn(async move {
+                    let _g = o.notice(1).await;
+                    lock(&order).push(n);
+                    tokio::task::yield_now().await;
+                })
+            })
+            .collect();
+        // Let each task register in turn.
+        tokio::task::yield_now().await;
+        drop(g);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*lock(&order), [0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn serves_higher_priorities_first() {
+        let o = AsyncObserver::new();
+        let g = o.notice(1).await;
+        let mut low = o.notice_with_priority(1, -1);
+        let mut normal = o.notice(1);
+        let mut high = o.notice_with_priority(1, 1);
+        let mut normal_later = o.notice(1);
+        for waiter in [&mut low, &mut normal, &mut high, &mut normal_later] {
+            assert!(poll_once(waiter).await.is_pending());
+        }
+        drop(g);
+        let g = poll_once(&mut high).await;
+        assert!(g.is_ready());
+        drop(g);
+        assert!(poll_once(&mut normal_later).await.is_pending());
+        assert!(poll_once(&mut low).await.is_pending());
+        drop(poll_once(&mut normal).await);
+        assert!(poll_once(&mut low).await.is_pending());
+        drop(poll_once(&mut normal_later).await);
+        assert!(poll_once(&mut low).await.is_ready());
+    }
+
+    #[tokio::test]
+    async fn hands_off_past_newcomers_and_dropped_waiters() {
+        let o = AsyncObserver::new();
+        let g = o.notice(1).await;
+        let mut first = o.notice(1);
+        let mut second = o.notice(1);
+        assert!(poll_once(&mut first).await.is_pending());
+        assert!(poll_once(&mut second).await.is_pending());
+        drop(g);
+        assert!(o.notice_now(1).is_none());
+        drop(first);
+        assert!(o.notice_now(1).is_none());
+        assert!(poll_once(&mut second).await.is_ready());
+        assert!(o.is_empty());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn notice_deadline_gives_up() {
+        let o = AsyncObserver::new();
+        let g = o.notice(1).await;
+        let deadline = Instant::now() + Duration::from_millis(20);
+        assert_eq!(o.notice_deadline(1, deadline).await.unwrap_err(), Timeout);
+        drop(g);
+        let g = o.notice_timeout(1, Duration::from_secs(60)).await.unwrap();
+        assert_eq!(*g.item(), 1);
+    }
+}
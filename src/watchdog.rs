@@ -0,0 +1,117 @@
+use crate::{Iterable, Observer};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/**
+Watchdog reports the observations that have been held longer than a
+threshold, which usually means a guard was leaked or a task is stuck. It is
+available with the `timestamps` feature.
+
+Call [`check`](Self::check) periodically, e.g. after each batch of work or
+from a timer, to have each overdue item passed to the callback.
+```
+use forgetful::{Observer, Watchdog};
+use std::time::Duration;
+
+let observer = Observer::new();
+let _stuck = observer.notice("request 7");
+std::thread::sleep(Duration::from_millis(20));
+let _fresh = observer.notice("request 8");
+
+let mut overdue = Vec::new();
+let mut watchdog = Watchdog::new(Duration::from_millis(10), |item, _held| overdue.push(item));
+assert_eq!(watchdog.check(&observer), 1);
+assert_eq!(overdue, ["request 7"]);
+```
+*/
+pub struct Watchdog<F> {
+    threshold: Duration,
+    on_overdue: F,
+}
+
+impl<F> Watchdog<F> {
+    /// Creates a watchdog that passes each item held longer than
+    /// `threshold` to `on_overdue`, along with how long it has been held.
+    pub fn new(threshold: Duration, on_overdue: F) -> Self {
+        Self {
+            threshold,
+            on_overdue,
+        }
+    }
+
+    /// Returns the threshold.
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Reports the items of `observer` held longer than the threshold,
+    /// longest first, and returns how many there were.
+    pub fn check<'a, T, R>(&mut self, observer: &Observer<'a, T, R>) -> usize
+    where
+        T: 'a + ?Sized,
+        R: Iterable<&'a T>,
+        F: FnMut(&'a T, Duration),
+    {
+        let overdue = observer.overdue(self.threshold);
+        for &(item, held) in &overdue {
+            (self.on_overdue)(item, held);
+        }
+        overdue.len()
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Iterable<&'a T>,
+{
+    /// Returns the items held longer than `threshold`, with how long each
+    /// has been held, longest first. See also [`Watchdog`].
+    pub fn overdue(&self, threshold: Duration) -> Vec<(&'a T, Duration)> {
+        let now = Instant::now();
+        let recorder = RefCell::borrow(&self.shared.recorder);
+        let mut overdue: Vec<_> = recorder
+            .iter()
+            .filter_map(|item| Some((*item, now - recorder.entry(item)?.created()?)))
+            .filter(|(_, held)| *held > threshold)
+            .collect();
+        overdue.sort_by(|(_, a), (_, b)| b.cmp(a));
+        overdue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_overdue_items_longest_first() {
+        let o = Observer::new();
+        let _a = o.notice(&1);
+        std::thread::sleep(Duration::from_millis(2));
+        let _b = o.notice(&2);
+        std::thread::sleep(Duration::from_millis(2));
+        let overdue: Vec<_> = o
+            .overdue(Duration::ZERO)
+            .into_iter()
+            .map(|(item, _)| item)
+            .collect();
+        assert_eq!(overdue, [&1, &2]);
+        assert!(o.overdue(Duration::from_secs(60)).is_empty());
+    }
+
+    #[test]
+    fn watchdog_calls_back_for_each_item() {
+        let o = Observer::new();
+        let _a = o.notice(&1);
+        let mut seen = 0;
+        {
+            let mut watchdog = Watchdog::new(Duration::ZERO, |_: &i32, _| seen += 1);
+            std::thread::sleep(Duration::from_millis(1));
+            assert_eq!(watchdog.threshold(), Duration::ZERO);
+            assert_eq!(watchdog.check(&o), 1);
+        }
+        assert_eq!(seen, 1);
+    }
+}
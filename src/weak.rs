@@ -0,0 +1,106 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, Observation, Observer, Recorder, Shared};
+use alloc::rc::{Rc, Weak};
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+
+/**
+A handle to an [`Observer`] that doesn't keep it alive, made by
+[`Observer::downgrade`].
+
+Long-lived callbacks can hold one without keeping the observer's items
+around after everything else is done with it. The recorder lives on as long
+as any [`Observer`] handle or [`Observation`] does.
+```
+use forgetful::Observer;
+let observer = Observer::new();
+let weak = observer.downgrade();
+assert!(weak.notice("foo").is_some());
+drop(observer);
+assert!(weak.notice("foo").is_none());
+assert!(weak.upgrade().is_none());
+```
+*/
+pub struct WeakObserver<'a, T, R = HashMap<&'a T, Entry, DefaultHashBuilder>>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    shared: Weak<Shared<&'a T, R>>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T, R> Clone for WeakObserver<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shared: Weak::clone(&self.shared),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, R> Debug for WeakObserver<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(WeakObserver)")
+    }
+}
+
+impl<'a, T, R> Observer<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Returns a [`WeakObserver`] handle to this observer.
+    pub fn downgrade(&self) -> WeakObserver<'a, T, R> {
+        WeakObserver {
+            shared: Rc::downgrade(&self.shared),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, R> WeakObserver<'a, T, R>
+where
+    T: 'a + ?Sized,
+    R: Recorder<&'a T>,
+{
+    /// Returns a strong handle to the observer, or None if it is gone.
+    pub fn upgrade(&self) -> Option<Observer<'a, T, R>> {
+        Some(Observer {
+            shared: self.shared.upgrade()?,
+            marker: PhantomData,
+        })
+    }
+
+    /// Notices `item` in the observer, or returns None if either it is
+    /// already observed or the observer is gone. See [`Observer::notice`].
+    #[track_caller]
+    pub fn notice(&self, item: &'a T) -> Option<Observation<'a, T, R>> {
+        self.upgrade()?.notice(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Observer;
+
+    #[test]
+    fn outstanding_observations_keep_the_recorder() {
+        let o = Observer::new();
+        let weak = o.downgrade();
+        let g = o.notice(&1).unwrap();
+        drop(o);
+        let o = weak.upgrade().unwrap();
+        assert!(weak.notice(&1).is_none());
+        drop((g, o));
+        assert!(weak.clone().notice(&1).is_none());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::hash::{hash_map, DefaultHashBuilder, HashMap};
+use crate::{Entry, Iterable, Lookup, Observation, Observer, Recorder};
+use alloc::borrow::Borrow;
+use core::cell::RefCell;
+use core::cmp::Eq;
+use core::fmt::{self, Debug, Display};
+use core::hash::{BuildHasher, Hash};
+
+/// The error returned by [`Observer::notice_weighted`] when an observation
+/// would take the observer over its budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeightError {
+    weight: usize,
+    remaining: usize,
+}
+
+impl WeightError {
+    /// The weight of the rejected observation.
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    /// The weight that was still available when it was rejected.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl Display for WeightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "observation of weight {} exceeds the remaining budget of {}",
+            self.weight, self.remaining
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WeightError {}
+
+/**
+WeightedRecorder is a [`Recorder`] that gives each item a weight, and caps
+the total weight of the items recorded at once.
+
+Items recorded through [`Recorder::insert`] weigh 1; use
+[`insert_weighted`](Self::insert_weighted) to pick the weight.
+*/
+pub struct WeightedRecorder<K, S = DefaultHashBuilder> {
+    budget: usize,
+    used: usize,
+    items: HashMap<K, (usize, Entry), S>,
+}
+
+impl<K> WeightedRecorder<K> {
+    /// Creates a recorder whose items may weigh up to `budget` in total.
+    pub fn new(budget: usize) -> Self {
+        Self::with_hasher(budget, Default::default())
+    }
+}
+
+impl<K, S> WeightedRecorder<K, S> {
+    /// Like [`WeightedRecorder::new`], but using `hasher` to hash items.
+    pub fn with_hasher(budget: usize, hasher: S) -> Self {
+        Self {
+            budget,
+            used: 0,
+            items: HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// Returns the total weight allowed.
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Returns the total weight of the items currently recorded.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+}
+
+impl<K, S> WeightedRecorder<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Records `item` with the given weight, returning `Ok(false)` if it was
+    /// already recorded, or a [`WeightError`] if it isn't but doesn't fit in
+    /// the remaining budget.
+    pub fn insert_weighted(
+        &mut self,
+        item: K,
+        weight: usize,
+        entry: Entry,
+    ) -> Result<bool, WeightError> {
+        let remaining = self.budget - self.used;
+        match self.items.entry(item) {
+            hash_map::Entry::Occupied(_) => Ok(false),
+            hash_map::Entry::Vacant(_) if weight > remaining => {
+                Err(WeightError { weight, remaining })
+            }
+            hash_map::Entry::Vacant(vacant) => {
+                vacant.insert((weight, entry));
+                self.used += weight;
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl<K, S> Debug for WeightedRecorder<K, S>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.items.iter().map(|(item, (weight, _))| (item, weight)))
+            .finish()
+    }
+}
+
+impl<K, S> Recorder<K> for WeightedRecorder<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        self.insert_weighted(item, 1, entry).unwrap_or(false)
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        match self.items.remove(item) {
+            Some((weight, _)) => {
+                self.used -= weight;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        self.items.contains_key(item)
+    }
+
+    fn can_insert(&self, item: &K) -> bool {
+        !self.contains(item) && self.used < self.budget
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        self.items.get(item).map(|(_, entry)| entry)
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.used = 0;
+    }
+
+    fn reset_generations(&mut self) {
+        for (_, entry) in self.items.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+impl<K, S> Iterable<K> for WeightedRecorder<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Iter<'r>
+        = hash_map::Keys<'r, K, (usize, Entry)>
+    where
+        Self: 'r,
+        K: 'r;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.items.keys()
+    }
+}
+
+impl<K, Q, S> Lookup<K, Q> for WeightedRecorder<K, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        self.items.contains_key(item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        self.items.get(item).map(|(_, entry)| entry)
+    }
+}
+
+/**
+WeightedObserver is an [`Observer`] that bounds the total weight of its
+current observations, backed by a [`WeightedRecorder`].
+
+This limits the cost of in-flight work, not just its uniqueness. Plain
+[`notice`](Observer::notice) counts as weight 1, and returns `None` if even
+that doesn't fit.
+```
+use forgetful::WeightedObserver;
+let observer = WeightedObserver::with_budget(100);
+let big = observer.notice_weighted("big.iso", 80).unwrap().expect("never seen before");
+assert!(observer.notice_weighted("other.iso", 30).is_err());
+drop(big);
+assert!(observer.notice_weighted("other.iso", 30).unwrap().is_some());
+```
+*/
+pub type WeightedObserver<'a, T> = Observer<'a, T, WeightedRecorder<&'a T>>;
+
+/// The observation type of [`WeightedObserver`].
+pub type WeightedObservation<'a, T> = Observation<'a, T, WeightedRecorder<&'a T>>;
+
+impl<'a, T> Observer<'a, T, WeightedRecorder<&'a T>>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Creates an observer backed by [`WeightedRecorder::new`].
+    pub fn with_budget(budget: usize) -> Self {
+        Self::with_recorder(WeightedRecorder::new(budget))
+    }
+
+    /// Notices `item` with the given weight, returning `Ok(None)` if it is
+    /// already observed, or a [`WeightError`] if it isn't but would take the
+    /// observer over its budget.
+    #[track_caller]
+    pub fn notice_weighted(
+        &self,
+        item: &'a T,
+        weight: usize,
+    ) -> Result<Option<WeightedObservation<'a, T>>, WeightError> {
+        let inserted =
+            self.shared
+                .recorder
+                .borrow_mut()
+                .insert_weighted(item, weight, self.shared.entry())?;
+        Ok(if inserted {
+            Some(self.observation(item))
+        } else {
+            self.shared.reject(&item);
+            None
+        })
+    }
+
+    /// Returns the total weight of the current observations.
+    pub fn used(&self) -> usize {
+        RefCell::borrow(&self.shared.recorder).used()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_observations_over_budget() {
+        let o = WeightedObserver::with_budget(10);
+        let g1 = o.notice_weighted(&1, 6).unwrap();
+        assert!(g1.is_some());
+        let err = o.notice_weighted(&2, 5).unwrap_err();
+        assert_eq!((err.weight(), err.remaining()), (5, 4));
+        let _g2 = o.notice_weighted(&2, 4).unwrap().unwrap();
+        assert_eq!(o.used(), 10);
+        assert!(o.notice(&3).is_none());
+        drop(g1);
+        assert_eq!(o.used(), 4);
+        assert!(o.notice(&3).is_some());
+    }
+
+    #[test]
+    fn items_over_budget_are_not_duplicates() {
+        use crate::NoticeError;
+        use core::cell::Cell;
+        use std::rc::Rc;
+
+        let o = WeightedObserver::with_budget(1).strict();
+        let duplicates = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&duplicates);
+        o.on_duplicate(move |_, _| counter.set(counter.get() + 1));
+        let _g = o.notice(&1).unwrap();
+        assert_eq!(o.notice_checked(&2).unwrap_err(), NoticeError::Refused(&2));
+        let (claimed, unclaimed) = o.notice_any(&[2, 3]);
+        assert!(claimed.is_empty());
+        assert_eq!(unclaimed, [&2, &3]);
+        assert!(o.notice_all(&[2]).is_none());
+        assert_eq!((o.stats().duplicates(), duplicates.get()), (0, 0));
+        assert!(!o.is_observing(&2));
+    }
+
+    #[test]
+    fn duplicates_are_not_errors() {
+        let o = WeightedObserver::with_budget(10);
+        let _g = o.notice_weighted(&1, 10).unwrap();
+        assert!(o.notice_weighted(&1, 10).unwrap().is_none());
+    }
+}
@@ -0,0 +1,280 @@
+use crate::hash::{DefaultHashBuilder, HashMap};
+use crate::{Entry, Lookup, Observation, Observer, Recorder};
+use alloc::borrow::Borrow;
+use alloc::collections::VecDeque;
+use core::cmp::Eq;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// How long a [`WindowRecorder`] keeps remembering an item after it is
+/// removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Window {
+    /// For this many more inserts, of any item.
+    Notices(u64),
+    /// For this long. Available with the `std` feature.
+    #[cfg(feature = "std")]
+    Time(Duration),
+}
+
+/// When an item was removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Removal {
+    /// The number of inserts before the removal.
+    inserts: u64,
+    #[cfg(feature = "std")]
+    at: Instant,
+}
+
+/**
+WindowRecorder is a [`Recorder`] that keeps remembering each item for a
+[`Window`] after it is removed, so that near-duplicates are suppressed for a
+while rather than only while the item is held.
+*/
+pub struct WindowRecorder<K, S = DefaultHashBuilder> {
+    window: Window,
+    inserts: u64,
+    /// Each item's entry, with when it was removed, if it was.
+    items: HashMap<K, (Entry, Option<Removal>), S>,
+    /// The removed items, in the order they were removed.
+    removed: VecDeque<(K, Removal)>,
+}
+
+impl<K> WindowRecorder<K> {
+    /// Creates a recorder that remembers removed items for `window`.
+    pub fn new(window: Window) -> Self {
+        Self::with_hasher(window, Default::default())
+    }
+}
+
+impl<K, S> WindowRecorder<K, S> {
+    /// Like [`WindowRecorder::new`], but using `hasher` to hash items.
+    pub fn with_hasher(window: Window, hasher: S) -> Self {
+        Self {
+            window,
+            inserts: 0,
+            items: HashMap::with_hasher(hasher),
+            removed: VecDeque::new(),
+        }
+    }
+
+    /// Returns how long removed items are remembered.
+    pub fn window(&self) -> Window {
+        self.window
+    }
+
+    /// Returns true if the window since `removal` has passed.
+    fn has_passed(&self, removal: &Removal) -> bool {
+        match self.window {
+            Window::Notices(notices) => self.inserts - removal.inserts >= notices,
+            #[cfg(feature = "std")]
+            Window::Time(time) => removal.at.elapsed() >= time,
+        }
+    }
+}
+
+impl<K, S> WindowRecorder<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Forgets the removed items whose window has passed.
+    fn prune(&mut self) {
+        while let Some((item, removal)) = self.removed.front() {
+            if !self.has_passed(removal) {
+                break;
+            }
+            if self
+                .items
+                .get(item)
+                .is_some_and(|(_, r)| r == &Some(*removal))
+            {
+                self.items.remove(item);
+            }
+            self.removed.pop_front();
+        }
+    }
+
+    /// Returns true if `item` is remembered: held, or removed within the
+    /// window.
+    fn remembers<Q>(&self, item: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        match self.items.get(item) {
+            Some((_, Some(removal))) => !self.has_passed(removal),
+            Some((_, None)) => true,
+            None => false,
+        }
+    }
+}
+
+impl<K, S> Debug for WindowRecorder<K, S>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.items.keys()).finish()
+    }
+}
+
+impl<K, S> Recorder<K> for WindowRecorder<K, S>
+where
+    K: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    fn insert(&mut self, item: K, entry: Entry) -> bool {
+        self.inserts += 1;
+        self.prune();
+        if self.remembers(&item) {
+            return false;
+        }
+        self.items.insert(item, (entry, None));
+        true
+    }
+
+    fn remove(&mut self, item: &K) -> bool {
+        let removal = Removal {
+            inserts: self.inserts,
+            #[cfg(feature = "std")]
+            at: Instant::now(),
+        };
+        match self.items.get_mut(item) {
+            Some((_, held @ None)) => *held = Some(removal),
+            _ => return false,
+        }
+        self.removed.push_back((item.clone(), removal));
+        true
+    }
+
+    fn undo_insert(&mut self, item: &K) -> bool {
+        match self.items.get(item) {
+            Some((_, None)) => self.items.remove(item).is_some(),
+            _ => false,
+        }
+    }
+
+    fn contains(&self, item: &K) -> bool {
+        self.remembers(item)
+    }
+
+    fn entry(&self, item: &K) -> Option<&Entry> {
+        match self.items.get(item) {
+            Some((entry, _)) if self.remembers(item) => Some(entry),
+            _ => None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.items
+            .keys()
+            .filter(|item| self.remembers(item))
+            .count()
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.removed.clear();
+    }
+
+    fn reset_generations(&mut self) {
+        for (entry, _) in self.items.values_mut() {
+            *entry = entry.in_generation(0);
+        }
+    }
+}
+
+impl<K, Q, S> Lookup<K, Q> for WindowRecorder<K, S>
+where
+    K: Eq + Hash + Clone + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    fn contains_ref(&self, item: &Q) -> bool {
+        self.remembers(item)
+    }
+
+    fn entry_ref(&self, item: &Q) -> Option<&Entry> {
+        match self.items.get(item) {
+            Some((entry, _)) if self.remembers(item) => Some(entry),
+            _ => None,
+        }
+    }
+}
+
+/**
+WindowObserver is an [`Observer`] that keeps suppressing each item for a
+while after its observation is dropped, as suits deduplicating a stream.
+```
+use forgetful::{Window, WindowObserver};
+let observer = WindowObserver::with_window(Window::Notices(2));
+drop(observer.notice("ping"));
+assert!(observer.notice("ping").is_none());
+assert!(observer.notice("pong").is_some());
+// Two notices have been made since, so "ping" is forgotten.
+assert!(observer.notice("ping").is_some());
+```
+*/
+pub type WindowObserver<'a, T> = Observer<'a, T, WindowRecorder<&'a T>>;
+
+/// The observation type of [`WindowObserver`].
+pub type WindowObservation<'a, T> = Observation<'a, T, WindowRecorder<&'a T>>;
+
+impl<'a, T> Observer<'a, T, WindowRecorder<&'a T>>
+where
+    T: 'a + Eq + Hash + ?Sized,
+{
+    /// Creates an observer backed by [`WindowRecorder::new`].
+    pub fn with_window(window: Window) -> Self {
+        Self::with_recorder(WindowRecorder::new(window))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remembers_items_for_the_next_notices() {
+        let o = WindowObserver::with_window(Window::Notices(3));
+        let g = o.notice(&1).unwrap();
+        assert!(o.notice(&1).is_none());
+        drop(g);
+        assert!(o.is_observing(&1));
+        assert!(o.notice(&1).is_none());
+        let _two = o.notice(&2).unwrap();
+        assert_eq!(o.len(), 2);
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[test]
+    fn rolled_back_items_are_not_remembered() {
+        let o = WindowObserver::with_window(Window::Notices(100));
+        let _two = o.notice(&2).unwrap();
+        assert!(o.notice_all([&1, &2]).is_none());
+        assert!(!o.is_observing(&1));
+        let mut t = o.begin();
+        assert!(t.notice(&3));
+        t.rollback();
+        let mark = o.checkpoint();
+        let four = o.notice(&4).unwrap();
+        o.rollback_to(&mark);
+        drop(four);
+        assert!(!o.is_observing(&3) && !o.is_observing(&4));
+        assert!(o.notice(&1).is_some());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn remembers_items_for_a_time() {
+        let o = WindowObserver::with_window(Window::Time(Duration::from_millis(10)));
+        drop(o.notice(&1).unwrap());
+        assert!(o.notice(&1).is_none());
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!o.is_observing(&1));
+        assert!(o.notice(&1).is_some());
+    }
+}